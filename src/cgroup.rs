@@ -0,0 +1,243 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::disk::read_sys_string;
+
+pub const PROC_SELF_CGROUP: &str = "/proc/self/cgroup";
+pub const SYS_FS_CGROUP: &str = "/sys/fs/cgroup";
+
+/// cgroup v1 中不限制内存时 limit_in_bytes 为接近 i64::MAX 的值(按页对齐)
+const V1_UNLIMITED: u64 = 1 << 62;
+
+/// 当前进程所在 cgroup 的有效限制，没有限制的字段为 None
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CgroupLimits {
+    /// 1 或 2
+    pub version: u8,
+    /// CPU 配额，单位为 CPU 个数，例如 cpu.max 为 "200000 100000" 时为 2.0
+    pub cpu_quota: Option<f64>,
+    /// 允许使用的 CPU 列表，例如 0-3
+    pub cpuset: Option<String>,
+    pub memory_limit: Option<u64>,
+    pub memory_usage: Option<u64>,
+    pub swap_limit: Option<u64>,
+}
+
+/// /proc/self/cgroup 中的一行: 4:memory:/kubepods/pod1，v2 为 0::/kubepods/pod1
+#[derive(Debug, Clone, PartialEq)]
+pub struct CgroupEntry {
+    pub controllers: Vec<String>,
+    pub path: String,
+}
+
+pub fn parse_proc_cgroup(text: &str) -> Vec<CgroupEntry> {
+    let mut entries = Vec::new();
+    for line in text.lines() {
+        let mut fields = line.splitn(3, ':');
+        let (Some(_), Some(controllers), Some(path)) = (fields.next(), fields.next(), fields.next()) else { continue };
+        entries.push(CgroupEntry {
+            controllers: controllers.split(',').filter(|c| !c.is_empty()).map(|c| c.to_string()).collect(),
+            path: path.to_string(),
+        });
+    }
+    entries
+}
+
+/// 从当前 cgroup 到挂载点根目录的全部目录，子目录在前，不存在的目录忽略
+/// 容器内使用 cgroup namespace 时路径通常为 /，挂载点本身就是容器的 cgroup
+fn cgroup_dirs(mount: &Path, path: &str) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    let mut path = Path::new(path.trim_start_matches('/'));
+    loop {
+        let dir = mount.join(path);
+        if dir.is_dir() {
+            dirs.push(dir);
+        }
+        match path.parent() {
+            Some(parent) => path = parent,
+            None => break,
+        }
+    }
+    dirs
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    read_sys_string(path)?.parse().ok()
+}
+
+/// 取各级 cgroup 中最小的限制
+fn min_limit(dirs: &[PathBuf], read: impl Fn(&Path) -> Option<u64>) -> Option<u64> {
+    min_limit_dir(dirs, read).map(|(_, limit)| limit)
+}
+
+/// 最小的限制及其所在的目录，限制相同时取子目录
+fn min_limit_dir(dirs: &[PathBuf], read: impl Fn(&Path) -> Option<u64>) -> Option<(&PathBuf, u64)> {
+    dirs.iter().filter_map(|dir| Some((dir, read(dir)?))).min_by_key(|(_, limit)| *limit)
+}
+
+/// 读取当前进程的 cgroup 限制，cgroup_root 为 cgroup 文件系统的挂载点(测试时为伪造的目录)
+pub fn read_cgroup_limits(proc_self_cgroup: &Path, cgroup_root: &Path) -> Option<CgroupLimits> {
+    let entries = parse_proc_cgroup(&fs::read_to_string(proc_self_cgroup).ok()?);
+    if cgroup_root.join("cgroup.controllers").exists() {
+        let entry = entries.iter().find(|e| e.controllers.is_empty())?;
+        Some(read_v2(&cgroup_dirs(cgroup_root, &entry.path)))
+    } else {
+        Some(read_v1(&entries, cgroup_root))
+    }
+}
+
+/// cgroup v2 的限制值，max 为不限制
+fn parse_max(text: &str) -> Option<u64> {
+    if text == "max" { None } else { text.parse().ok() }
+}
+
+/// cpu.max 为 "配额 周期"，返回 CPU 个数，配额为 max 时不限制
+fn parse_cpu_max(text: &str) -> Option<f64> {
+    let (quota, period) = text.split_once(' ')?;
+    let (quota, period): (f64, f64) = (quota.parse().ok()?, period.parse().ok()?);
+    Some(quota / period)
+}
+
+/// cgroup v2: cpu.max、cpuset.cpus.effective、memory.max、memory.current、memory.swap.max
+fn read_v2(dirs: &[PathBuf]) -> CgroupLimits {
+    let read_max = |path: &Path| parse_max(&read_sys_string(path)?);
+    let cpu_quota = dirs.iter().filter_map(|dir| parse_cpu_max(&read_sys_string(&dir.join("cpu.max"))?)).reduce(f64::min);
+
+    //限制来自上级 cgroup 时，使用量也从同一级读取，否则 Use% 是子 cgroup 的使用量除以上级的限制
+    let memory = min_limit_dir(dirs, |dir| read_max(&dir.join("memory.max")));
+    let usage_dir = memory.map(|(dir, _)| dir).or(dirs.first());
+
+    CgroupLimits {
+        version: 2,
+        cpu_quota,
+        //cpuset.cpus.effective 已经是与各级上级取交集后的结果
+        cpuset: dirs.first().and_then(|dir| read_sys_string(&dir.join("cpuset.cpus.effective"))),
+        memory_limit: memory.map(|(_, limit)| limit),
+        memory_usage: usage_dir.and_then(|dir| read_u64(&dir.join("memory.current"))),
+        swap_limit: min_limit(dirs, |dir| read_max(&dir.join("memory.swap.max"))),
+    }
+}
+
+/// cgroup v1: 每个控制器单独挂载在 cgroup_root/控制器名 下
+fn read_v1(entries: &[CgroupEntry], cgroup_root: &Path) -> CgroupLimits {
+    let dirs = |controller: &str| -> Vec<PathBuf> {
+        entries.iter()
+            .find(|e| e.controllers.iter().any(|c| c == controller))
+            .map(|e| cgroup_dirs(&cgroup_root.join(controller), &e.path))
+            .unwrap_or_default()
+    };
+    let read_limit = |path: &Path| read_u64(path).filter(|v| *v < V1_UNLIMITED);
+
+    let cpu_dirs = dirs("cpu");
+    let cpu_quota = cpu_dirs.iter().filter_map(|dir| {
+        let quota: i64 = read_sys_string(&dir.join("cpu.cfs_quota_us"))?.parse().ok()?;
+        let period = read_u64(&dir.join("cpu.cfs_period_us"))?;
+        if quota <= 0 || period == 0 {
+            return None; //-1 不限制
+        }
+        Some(quota as f64 / period as f64)
+    }).reduce(f64::min);
+
+    let memory_dirs = dirs("memory");
+    let memory = min_limit_dir(&memory_dirs, |dir| read_limit(&dir.join("memory.limit_in_bytes")));
+    let memory_limit = memory.map(|(_, limit)| limit);
+    let usage_dir = memory.map(|(dir, _)| dir).or(memory_dirs.first());
+    //memsw 是内存和交换分区的总和
+    let swap_limit = min_limit(&memory_dirs, |dir| read_limit(&dir.join("memory.memsw.limit_in_bytes")))
+        .map(|memsw| memsw.saturating_sub(memory_limit.unwrap_or_default()));
+
+    CgroupLimits {
+        version: 1,
+        cpu_quota,
+        cpuset: dirs("cpuset").first().and_then(|dir| read_sys_string(&dir.join("cpuset.cpus"))),
+        memory_limit,
+        memory_usage: usage_dir.and_then(|dir| read_u64(&dir.join("memory.usage_in_bytes"))),
+        swap_limit,
+    }
+}
+
+#[test]
+fn test_parse_proc_cgroup() {
+    let entries = parse_proc_cgroup("4:memory:/kubepods/pod1\n2:cpu,cpuacct:/kubepods/pod1\n0::/\n");
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[1].controllers, vec!["cpu", "cpuacct"]);
+    assert_eq!(entries[1].path, "/kubepods/pod1");
+    assert!(entries[2].controllers.is_empty());
+}
+
+#[test]
+fn test_parse_max() {
+    assert_eq!(parse_max("4294967296"), Some(4294967296));
+    assert_eq!(parse_max("max"), None);
+    assert_eq!(parse_cpu_max("200000 100000"), Some(2.));
+    assert_eq!(parse_cpu_max("50000 100000"), Some(0.5));
+    assert_eq!(parse_cpu_max("max 100000"), None);
+}
+
+#[test]
+fn test_read_cgroup_limits() {
+    let root = crate::common::test_dir("cgroup");
+
+    //cgroup v2
+    let proc_self_cgroup = root.join("cgroup-v2");
+    fs::write(&proc_self_cgroup, "0::/kubepods/pod1\n").unwrap();
+    let mount = root.join("v2");
+    let pod = mount.join("kubepods/pod1");
+    fs::create_dir_all(&pod).unwrap();
+    fs::write(mount.join("cgroup.controllers"), "cpuset cpu memory\n").unwrap();
+    fs::write(mount.join("kubepods/memory.max"), "8589934592\n").unwrap();
+    fs::write(pod.join("memory.max"), "4294967296\n").unwrap();
+    fs::write(pod.join("memory.current"), "1073741824\n").unwrap();
+    fs::write(pod.join("memory.swap.max"), "max\n").unwrap();
+    fs::write(pod.join("cpu.max"), "200000 100000\n").unwrap();
+    fs::write(pod.join("cpuset.cpus.effective"), "0-3\n").unwrap();
+
+    let limits = read_cgroup_limits(&proc_self_cgroup, &mount).unwrap();
+    assert_eq!(limits, CgroupLimits {
+        version: 2,
+        cpu_quota: Some(2.),
+        cpuset: Some("0-3".to_string()),
+        memory_limit: Some(4294967296),
+        memory_usage: Some(1073741824),
+        swap_limit: None,
+    });
+
+    //限制来自上级 cgroup 时，使用量也取上级的
+    fs::write(mount.join("kubepods/memory.max"), "2147483648\n").unwrap();
+    fs::write(mount.join("kubepods/memory.current"), "1610612736\n").unwrap();
+    let limits = read_cgroup_limits(&proc_self_cgroup, &mount).unwrap();
+    assert_eq!((limits.memory_limit, limits.memory_usage), (Some(2147483648), Some(1610612736)));
+
+    //cgroup namespace: 路径为 /，挂载点就是容器的 cgroup
+    fs::write(&proc_self_cgroup, "0::/\n").unwrap();
+    fs::write(mount.join("memory.max"), "2147483648\n").unwrap();
+    assert_eq!(read_cgroup_limits(&proc_self_cgroup, &mount).unwrap().memory_limit, Some(2147483648));
+
+    //cgroup v1: 每个控制器单独挂载
+    let proc_self_cgroup = root.join("cgroup-v1");
+    fs::write(&proc_self_cgroup, "4:memory:/docker/abc\n3:cpuset:/docker/abc\n2:cpu,cpuacct:/docker/abc\n").unwrap();
+    let mount = root.join("v1");
+    for controller in ["memory", "cpuset", "cpu"] {
+        fs::create_dir_all(mount.join(controller).join("docker/abc")).unwrap();
+    }
+    fs::write(mount.join("memory/memory.limit_in_bytes"), "9223372036854771712\n").unwrap();
+    fs::write(mount.join("memory/docker/abc/memory.limit_in_bytes"), "536870912\n").unwrap();
+    fs::write(mount.join("memory/docker/abc/memory.memsw.limit_in_bytes"), "1073741824\n").unwrap();
+    fs::write(mount.join("memory/docker/abc/memory.usage_in_bytes"), "104857600\n").unwrap();
+    fs::write(mount.join("cpu/docker/abc/cpu.cfs_quota_us"), "150000\n").unwrap();
+    fs::write(mount.join("cpu/docker/abc/cpu.cfs_period_us"), "100000\n").unwrap();
+    fs::write(mount.join("cpu/cpu.cfs_quota_us"), "-1\n").unwrap();
+    fs::write(mount.join("cpu/cpu.cfs_period_us"), "100000\n").unwrap();
+    fs::write(mount.join("cpuset/docker/abc/cpuset.cpus"), "0,2\n").unwrap();
+
+    let limits = read_cgroup_limits(&proc_self_cgroup, &mount).unwrap();
+    assert_eq!(limits, CgroupLimits {
+        version: 1,
+        cpu_quota: Some(1.5),
+        cpuset: Some("0,2".to_string()),
+        memory_limit: Some(536870912),
+        memory_usage: Some(104857600),
+        swap_limit: Some(536870912),
+    });
+    assert!(read_cgroup_limits(&root.join("missing"), &mount).is_none());
+}
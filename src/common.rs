@@ -1,6 +1,7 @@
 use std::fmt;
 use std::error;
 use std::str::FromStr;
+use std::time::Duration;
 
 /// 1B(Byte，字节)=8位(bit)
 /// 1KB(KiloByte，千字节)=1024B
@@ -106,6 +107,7 @@ const SIZE_1000: Size = Size {
 };
 
 pub trait PrettySize {
+    /// 默认单位: 1024进制，自动选择单位
     fn pretty_size(self) -> String;
     fn pretty_size_with(self, base: BaseSize, block: BlockSize) -> String;
 }
@@ -171,6 +173,133 @@ fn format_auto(num: u64, base: BaseSize) -> String {
     }
 }
 
+fn local_tm(secs: i64) -> Option<libc::tm> {
+    let time = secs as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::localtime_r(&time, &mut tm) };
+    if result.is_null() {
+        None
+    } else {
+        Some(tm)
+    }
+}
+
+/// 格式化本地时间，例如 2024-04-11 08:30:00
+pub fn format_local_time(secs: i64) -> String {
+    let Some(tm) = local_tm(secs) else { return secs.to_string() };
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            tm.tm_year + 1900, tm.tm_mon + 1, tm.tm_mday, tm.tm_hour, tm.tm_min, tm.tm_sec)
+}
+
+/// RFC 3339 格式的本地时间，带时区偏移，例如 2024-04-11T08:30:00+08:00
+pub fn format_rfc3339(secs: i64) -> String {
+    let Some(tm) = local_tm(secs) else { return secs.to_string() };
+    let offset = tm.tm_gmtoff / 60;
+    let sign = if offset < 0 { '-' } else { '+' };
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{sign}{:02}:{:02}",
+            tm.tm_year + 1900, tm.tm_mon + 1, tm.tm_mday, tm.tm_hour, tm.tm_min, tm.tm_sec, offset.abs() / 60, offset.abs() % 60)
+}
+
+/// 运行时间，例如 3 days, 4 hours, 5 minutes
+pub fn format_uptime(secs: u64) -> String {
+    let days = secs / 86400;
+    let hours = secs % 86400 / 3600;
+    let minutes = secs % 3600 / 60;
+    let plural = |n: u64, unit: &str| if n == 1 { format!("{n} {unit}") } else { format!("{n} {unit}s") };
+
+    let mut parts = Vec::new();
+    if days > 0 {
+        parts.push(plural(days, "day"));
+    }
+    if days > 0 || hours > 0 {
+        parts.push(plural(hours, "hour"));
+    }
+    parts.push(plural(minutes, "minute"));
+    parts.join(", ")
+}
+
+/// 当前时间的 Unix 时间戳(秒)
+pub fn now_secs() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+#[derive(Debug)]
+pub struct DurationParseError;
+
+impl fmt::Display for DurationParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid Duration")
+    }
+}
+
+impl error::Error for DurationParseError {}
+
+/// 解析时间间隔，例如 500ms、1.5s、2m、1h，没有单位时按秒计算
+pub fn parse_duration(s: &str) -> Result<Duration, DurationParseError> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: f64 = number.parse().map_err(|_| DurationParseError)?;
+    let secs = match unit.trim() {
+        "ms" => number / 1000.,
+        "" | "s" => number,
+        "m" => number * 60.,
+        "h" => number * 3600.,
+        _ => return Err(DurationParseError),
+    };
+    Duration::try_from_secs_f64(secs).map_err(|_| DurationParseError)
+}
+
+/// 转换为 JSON 字符串(带引号)，转义引号、反斜杠和控制字符
+pub fn json_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// 测试用的临时目录(伪造 /sys、/proc 等目录结构)，创建时清空，drop 时删除，断言失败时也不会残留
+#[cfg(test)]
+pub(crate) struct TestDir(std::path::PathBuf);
+
+#[cfg(test)]
+impl std::ops::Deref for TestDir {
+    type Target = std::path::Path;
+
+    fn deref(&self) -> &std::path::Path {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn test_dir(name: &str) -> TestDir {
+    let dir = std::env::temp_dir().join(format!("sysinfo-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    TestDir(dir)
+}
+
 #[test]
 fn test() {
     let block_size: u64 = 4096;
@@ -182,6 +311,53 @@ fn test() {
     println!("2^10={}", other_a);
 }
 
+#[test]
+fn test_json_string() {
+    assert_eq!(json_string("/mnt/usb"), r#""/mnt/usb""#);
+    assert_eq!(json_string("a\"b\\c\nd\u{1}"), r#""a\"b\\c\nd\u0001""#);
+}
+
+#[test]
+fn test_parse_duration() {
+    assert_eq!(parse_duration("500ms").unwrap(), Duration::from_millis(500));
+    assert_eq!(parse_duration("1.5s").unwrap(), Duration::from_millis(1500));
+    assert_eq!(parse_duration("2").unwrap(), Duration::from_secs(2));
+    assert_eq!(parse_duration("2m").unwrap(), Duration::from_secs(120));
+    assert_eq!(parse_duration("1h").unwrap(), Duration::from_secs(3600));
+    assert!(parse_duration("").is_err());
+    assert!(parse_duration("1d").is_err());
+    assert!(parse_duration("ms").is_err());
+}
+
+#[test]
+fn test_format_uptime() {
+    assert_eq!(format_uptime(59), "0 minutes");
+    assert_eq!(format_uptime(3660), "1 hour, 1 minute");
+    assert_eq!(format_uptime(3 * 86400 + 4 * 3600 + 5 * 60), "3 days, 4 hours, 5 minutes");
+    assert_eq!(format_uptime(86400 + 120), "1 day, 0 hours, 2 minutes");
+}
+
+#[test]
+fn test_format_local_time() {
+    let text = format_local_time(0);
+    println!("{}", text);
+    assert_eq!(text.len(), "1970-01-01 00:00:00".len());
+    assert!(text.starts_with("1970-01-01") || text.starts_with("1969-12-31"));
+}
+
+#[test]
+fn test_format_rfc3339() {
+    let text = format_rfc3339(0);
+    assert_eq!(text.len(), "1970-01-01T00:00:00+00:00".len());
+    assert_eq!(&text[10..11], "T");
+    assert!(matches!(&text[19..20], "+" | "-"));
+    //本地时间减去偏移量应为 UTC 的 0 点
+    let hours: i64 = text[11..13].parse().unwrap();
+    let offset: i64 = text[20..22].parse().unwrap();
+    let utc = if &text[19..20] == "+" { hours - offset } else { hours + offset };
+    assert_eq!(utc.rem_euclid(24), 0);
+}
+
 #[test]
 fn test_block_size_parse() {
     let block_size: BlockSize = "B".parse().unwrap();
@@ -0,0 +1,379 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::disk::read_sys_string;
+
+pub const PROC_STAT: &str = "/proc/stat";
+pub const PROC_LOADAVG: &str = "/proc/loadavg";
+pub const PROC_CPUINFO: &str = "/proc/cpuinfo";
+pub const SYS_CPU: &str = "/sys/devices/system/cpu";
+pub const SYS_NODE: &str = "/sys/devices/system/node";
+pub const SYS_VULNERABILITIES: &str = "/sys/devices/system/cpu/vulnerabilities";
+
+/// 按用途分组的指令集标志，名称与 /proc/cpuinfo 的 flags 一致
+pub const FEATURE_GROUPS: [(&str, &[&str]); 7] = [
+    ("SSE", &["sse", "sse2", "pni", "ssse3", "sse4_1", "sse4_2"]),
+    ("AVX", &["avx", "avx2", "fma", "f16c", "avx_vnni"]),
+    ("AVX-512", &["avx512f", "avx512dq", "avx512cd", "avx512bw", "avx512vl", "avx512ifma", "avx512vbmi", "avx512_vbmi2", "avx512_vnni", "avx512_bf16", "avx512_fp16"]),
+    ("AES", &["aes", "vaes", "pclmulqdq", "vpclmulqdq"]),
+    ("SHA", &["sha_ni"]),
+    ("Virtualization", &["vmx", "svm", "hypervisor"]),
+    ("Other", &["bmi1", "bmi2", "popcnt", "rdrand", "rdseed", "adx"]),
+];
+
+/// CPU 状态名称，顺序与 /proc/stat 中的字段一致
+pub const CPU_STATES: [&str; 9] = ["user", "nice", "system", "idle", "iowait", "irq", "softirq", "steal", "guest"];
+
+/// /proc/stat 中 cpu/cpuN 行的累计时间(单位 USER_HZ)
+/// cpu0 4705 150 1120 16250 520 0 34 0 0 0
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CpuTimes {
+    pub name: String,
+    /// user nice system idle iowait irq softirq steal guest
+    pub times: [u64; 9],
+}
+
+impl CpuTimes {
+    /// 总时间，guest 已经计入 user，不重复累加
+    pub fn total(&self) -> u64 {
+        self.times[..8].iter().sum()
+    }
+
+    /// 两次采样之间各状态所占的百分比，顺序与 CPU_STATES 一致
+    pub fn breakdown(&self, prev: &CpuTimes) -> [f64; 9] {
+        let total = self.total().saturating_sub(prev.total());
+        let mut percent = [0.; 9];
+        if total == 0 {
+            return percent;
+        }
+        for (index, value) in percent.iter_mut().enumerate() {
+            *value = self.times[index].saturating_sub(prev.times[index]) as f64 / total as f64 * 100.;
+        }
+        percent
+    }
+}
+
+/// 解析 /proc/stat 中的 cpu 行，第一行为全部 CPU 的合计
+pub fn parse_stat(text: &str) -> Vec<CpuTimes> {
+    let mut cpus = Vec::new();
+    for line in text.lines() {
+        if !line.starts_with("cpu") {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let name = fields.next().unwrap_or_default().to_string();
+        let mut times = [0; 9];
+        //旧内核缺少后面的字段，按0处理
+        for (time, field) in times.iter_mut().zip(fields) {
+            *time = field.parse().unwrap_or_default();
+        }
+        cpus.push(CpuTimes { name, times });
+    }
+    cpus
+}
+
+pub fn read_stat(proc_stat: &Path) -> Vec<CpuTimes> {
+    fs::read_to_string(proc_stat).map(|text| parse_stat(&text)).unwrap_or_default()
+}
+
+/// /proc/loadavg 中的任务数: 0.20 0.18 0.12 1/80 11206 中的 1/80
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Tasks {
+    pub running: u32,
+    pub total: u32,
+}
+
+/// 解析 /proc/loadavg 的第4个字段，格式错误时返回 None
+pub fn parse_loadavg_tasks(text: &str) -> Option<Tasks> {
+    let (running, total) = text.split_whitespace().nth(3)?.split_once('/')?;
+    Some(Tasks {
+        running: running.parse().ok()?,
+        total: total.parse().ok()?,
+    })
+}
+
+pub fn read_tasks(proc_loadavg: &Path) -> Option<Tasks> {
+    parse_loadavg_tasks(&fs::read_to_string(proc_loadavg).ok()?)
+}
+
+/// 逻辑 CPU 的拓扑: 所在插槽、物理核心、超线程兄弟、NUMA 节点和缓存
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CpuTopology {
+    pub cpu: u32,
+    pub package: Option<u32>,
+    pub core: Option<u32>,
+    /// 共享同一物理核心的逻辑 CPU，例如 0,8
+    pub siblings: String,
+    pub node: Option<u32>,
+    /// 缓存名称(L1d/L1i/L2/L3)和大小
+    pub caches: Vec<(String, String)>,
+}
+
+/// cpufreq 频率调节信息，频率单位 MHz，虚拟机等没有 cpufreq 时各字段为 None
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CpuFreq {
+    pub cur: Option<u64>,
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+    pub governor: Option<String>,
+    /// energy_performance_preference，intel_pstate/amd-pstate 的 active 模式才有
+    pub epp: Option<String>,
+    pub boost: Option<bool>,
+}
+
+/// sysinfo 的 CPU 名称(cpuN)中的内核 CPU 编号，有离线 CPU 时与列表中的位置不同
+pub fn cpu_number(name: &str) -> Option<usize> {
+    name.strip_prefix("cpu")?.parse().ok()
+}
+
+/// 读取 /sys/devices/system/cpu/cpuN/cpufreq，boost 依次尝试 cpufreq/boost、全局 cpufreq/boost 和 intel_pstate/no_turbo
+pub fn read_cpufreq(sys_cpu: &Path, cpu: usize) -> CpuFreq {
+    let dir = sys_cpu.join(format!("cpu{cpu}")).join("cpufreq");
+    let read_mhz = |name: &str| read_sys_string(&dir.join(name)).and_then(|khz| khz.parse::<u64>().ok()).map(|khz| khz / 1000);
+    let boost = read_sys_string(&dir.join("boost"))
+        .or_else(|| read_sys_string(&sys_cpu.join("cpufreq/boost")))
+        .map(|boost| boost == "1")
+        .or_else(|| read_sys_string(&sys_cpu.join("intel_pstate/no_turbo")).map(|no_turbo| no_turbo == "0"));
+    CpuFreq {
+        cur: read_mhz("scaling_cur_freq"),
+        min: read_mhz("scaling_min_freq"),
+        max: read_mhz("scaling_max_freq"),
+        governor: read_sys_string(&dir.join("scaling_governor")),
+        epp: read_sys_string(&dir.join("energy_performance_preference")),
+        boost,
+    }
+}
+
+/// 解析 /proc/cpuinfo 中第一个 CPU 的指令集标志(x86 为 flags，ARM 为 Features)
+pub fn parse_cpu_flags(cpuinfo: &str) -> Vec<String> {
+    for line in cpuinfo.lines() {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        if matches!(key.trim(), "flags" | "Features") {
+            return value.split_whitespace().map(|flag| flag.to_string()).collect();
+        }
+    }
+    Vec::new()
+}
+
+/// 读取 CPU 漏洞及缓解措施状态，按名称排序
+pub fn read_vulnerabilities(dir: &Path) -> Vec<(String, String)> {
+    let mut vulnerabilities: Vec<(String, String)> = match fs::read_dir(dir) {
+        Ok(entries) => entries.flatten()
+            .filter_map(|entry| Some((entry.file_name().to_str()?.to_string(), read_sys_string(&entry.path())?)))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    vulnerabilities.sort();
+    vulnerabilities
+}
+
+/// 解析 CPU 列表，例如 0-3,8,10-11
+pub fn parse_cpu_list(text: &str) -> Vec<u32> {
+    let mut cpus = Vec::new();
+    for part in text.trim().split(',').filter(|part| !part.is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let (Ok(start), Ok(end)) = (start.parse::<u32>(), end.parse::<u32>()) else { continue };
+                cpus.extend(start..=end);
+            }
+            None => cpus.extend(part.parse::<u32>().ok()),
+        }
+    }
+    cpus
+}
+
+/// 读取全部逻辑 CPU 的拓扑，按 CPU 编号排序
+pub fn read_topology(sys_cpu: &Path, sys_node: &Path) -> Vec<CpuTopology> {
+    let nodes = read_nodes(sys_node);
+    let mut cpus: Vec<u32> = match fs::read_dir(sys_cpu) {
+        Ok(entries) => entries.flatten()
+            .filter_map(|entry| entry.file_name().to_str()?.strip_prefix("cpu")?.parse().ok())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    cpus.sort();
+
+    let mut topology = Vec::new();
+    for cpu in cpus {
+        let dir = sys_cpu.join(format!("cpu{cpu}"));
+        let read_id = |name: &str| read_sys_string(&dir.join("topology").join(name)).and_then(|id| id.parse().ok());
+        topology.push(CpuTopology {
+            cpu,
+            package: read_id("physical_package_id"),
+            core: read_id("core_id"),
+            siblings: read_sys_string(&dir.join("topology/thread_siblings_list")).unwrap_or_default(),
+            node: nodes.get(&cpu).copied(),
+            caches: read_caches(&dir.join("cache")),
+        });
+    }
+    topology
+}
+
+/// 逻辑 CPU 到 NUMA 节点的映射，来自 /sys/devices/system/node/nodeN/cpulist
+fn read_nodes(sys_node: &Path) -> HashMap<u32, u32> {
+    let mut nodes = HashMap::new();
+    let Ok(entries) = fs::read_dir(sys_node) else { return nodes };
+    for entry in entries.flatten() {
+        let Some(node) = entry.file_name().to_str().and_then(|name| name.strip_prefix("node")?.parse::<u32>().ok()) else { continue };
+        let cpulist = read_sys_string(&entry.path().join("cpulist")).unwrap_or_default();
+        for cpu in parse_cpu_list(&cpulist) {
+            nodes.insert(cpu, node);
+        }
+    }
+    nodes
+}
+
+/// 读取 cache/indexN/ 的级别、类型和大小，按级别排序
+fn read_caches(cache_dir: &Path) -> Vec<(String, String)> {
+    let mut caches = Vec::new();
+    let Ok(entries) = fs::read_dir(cache_dir) else { return caches };
+    for entry in entries.flatten() {
+        if !entry.file_name().to_string_lossy().starts_with("index") {
+            continue;
+        }
+        let dir = entry.path();
+        let (Some(level), Some(size)) = (read_sys_string(&dir.join("level")), read_sys_string(&dir.join("size"))) else { continue };
+        let name = match read_sys_string(&dir.join("type")).as_deref() {
+            Some("Data") => format!("L{level}d"),
+            Some("Instruction") => format!("L{level}i"),
+            _ => format!("L{level}"),
+        };
+        caches.push((name, size));
+    }
+    caches.sort();
+    caches
+}
+
+#[cfg(test)]
+const STAT: &str = "cpu  9410 300 2240 32500 1040 0 68 20 10 0
+cpu0 4705 150 1120 16250 520 0 34 10 5 0
+cpu1 4705 150 1120 16250 520 0 34 10 5 0
+intr 123456 0 0
+ctxt 987654
+";
+
+#[test]
+fn test_parse_stat() {
+    let cpus = parse_stat(STAT);
+    assert_eq!(cpus.len(), 3);
+    assert_eq!(cpus[0].name, "cpu");
+    assert_eq!(cpus[1].name, "cpu0");
+    assert_eq!(cpus[0].times, [9410, 300, 2240, 32500, 1040, 0, 68, 20, 10]);
+    assert_eq!(cpus[0].total(), 45578);
+
+    let old = parse_stat("cpu0 10 0 10 80\n");
+    assert_eq!(old[0].times, [10, 0, 10, 80, 0, 0, 0, 0, 0]);
+}
+
+#[test]
+fn test_breakdown() {
+    let prev = CpuTimes { name: "cpu".to_string(), times: [100, 0, 100, 700, 0, 0, 0, 0, 0] };
+    let cur = CpuTimes { name: "cpu".to_string(), times: [150, 0, 120, 780, 30, 5, 5, 10, 20] };
+    let percent = cur.breakdown(&prev);
+    assert_eq!(percent[0], 25.);
+    assert_eq!(percent[2], 10.);
+    assert_eq!(percent[3], 40.);
+    assert_eq!(percent[4], 15.);
+    assert_eq!(percent[7], 5.);
+    assert_eq!(percent[8], 10.);
+    assert_eq!(prev.breakdown(&prev), [0.; 9]);
+}
+
+#[test]
+fn test_parse_loadavg_tasks() {
+    assert_eq!(parse_loadavg_tasks("0.20 0.18 0.12 1/80 11206\n"), Some(Tasks { running: 1, total: 80 }));
+    assert_eq!(parse_loadavg_tasks("0.20 0.18 0.12"), None);
+}
+
+#[test]
+fn test_cpu_number() {
+    assert_eq!(cpu_number("cpu0"), Some(0));
+    assert_eq!(cpu_number("cpu12"), Some(12));
+    assert_eq!(cpu_number("cpu"), None);
+    assert_eq!(cpu_number("CPU 1"), None);
+}
+
+#[test]
+fn test_parse_cpu_list() {
+    assert_eq!(parse_cpu_list("0-3,8,10-11\n"), vec![0, 1, 2, 3, 8, 10, 11]);
+    assert_eq!(parse_cpu_list("5"), vec![5]);
+    assert!(parse_cpu_list("").is_empty());
+}
+
+#[test]
+fn test_parse_cpu_flags() {
+    let cpuinfo = "processor\t: 0\nvendor_id\t: GenuineIntel\nflags\t\t: fpu sse sse2 avx avx2 aes\n\nprocessor\t: 1\nflags\t\t: fpu\n";
+    assert_eq!(parse_cpu_flags(cpuinfo), vec!["fpu", "sse", "sse2", "avx", "avx2", "aes"]);
+    assert_eq!(parse_cpu_flags("processor\t: 0\nFeatures\t: fp asimd aes sha1\n"), vec!["fp", "asimd", "aes", "sha1"]);
+    assert!(parse_cpu_flags("").is_empty());
+}
+
+#[test]
+fn test_read_sys_cpu() {
+    let root = crate::common::test_dir("sys-cpu");
+    let sys_cpu = root.join("cpu");
+    let sys_node = root.join("node");
+    for (cpu, core, node) in [(0, 0, 0), (1, 1, 1), (2, 0, 0), (3, 1, 1)] {
+        let dir = sys_cpu.join(format!("cpu{cpu}"));
+        fs::create_dir_all(dir.join("topology")).unwrap();
+        fs::write(dir.join("topology/physical_package_id"), "0\n").unwrap();
+        fs::write(dir.join("topology/core_id"), format!("{core}\n")).unwrap();
+        fs::write(dir.join("topology/thread_siblings_list"), format!("{},{}\n", core, core + 2)).unwrap();
+        for (index, level, kind, size) in [(0, 1, "Data", "48K"), (1, 1, "Instruction", "32K"), (2, 2, "Unified", "2048K"), (3, 3, "Unified", "30720K")] {
+            let cache = dir.join(format!("cache/index{index}"));
+            fs::create_dir_all(&cache).unwrap();
+            fs::write(cache.join("level"), format!("{level}\n")).unwrap();
+            fs::write(cache.join("type"), format!("{kind}\n")).unwrap();
+            fs::write(cache.join("size"), format!("{size}\n")).unwrap();
+        }
+        fs::create_dir_all(sys_node.join(format!("node{node}"))).unwrap();
+    }
+    fs::write(sys_node.join("node0/cpulist"), "0,2\n").unwrap();
+    fs::write(sys_node.join("node1/cpulist"), "1,3\n").unwrap();
+
+    let freq_dir = sys_cpu.join("cpu1/cpufreq");
+    fs::create_dir_all(&freq_dir).unwrap();
+    fs::write(freq_dir.join("scaling_cur_freq"), "2400000\n").unwrap();
+    fs::write(freq_dir.join("scaling_min_freq"), "800000\n").unwrap();
+    fs::write(freq_dir.join("scaling_max_freq"), "4200000\n").unwrap();
+    fs::write(freq_dir.join("scaling_governor"), "powersave\n").unwrap();
+    fs::write(freq_dir.join("energy_performance_preference"), "balance_performance\n").unwrap();
+    fs::create_dir_all(sys_cpu.join("intel_pstate")).unwrap();
+    fs::write(sys_cpu.join("intel_pstate/no_turbo"), "1\n").unwrap();
+
+    fs::create_dir_all(sys_cpu.join("vulnerabilities")).unwrap();
+    fs::write(sys_cpu.join("vulnerabilities/spectre_v2"), "Mitigation: Enhanced IBRS\n").unwrap();
+    fs::write(sys_cpu.join("vulnerabilities/meltdown"), "Not affected\n").unwrap();
+
+    //cpufreq、vulnerabilities 等目录不是逻辑 CPU
+    let topology = read_topology(&sys_cpu, &sys_node);
+    assert_eq!(topology.len(), 4);
+    assert_eq!(topology[1].cpu, 1);
+    assert_eq!(topology[1].package, Some(0));
+    assert_eq!(topology[1].core, Some(1));
+    assert_eq!(topology[1].siblings, "1,3");
+    assert_eq!(topology[1].node, Some(1));
+    assert_eq!(topology[0].caches, vec![
+        ("L1d".to_string(), "48K".to_string()),
+        ("L1i".to_string(), "32K".to_string()),
+        ("L2".to_string(), "2048K".to_string()),
+        ("L3".to_string(), "30720K".to_string()),
+    ]);
+
+    let freq = read_cpufreq(&sys_cpu, 1);
+    assert_eq!((freq.cur, freq.min, freq.max), (Some(2400), Some(800), Some(4200)));
+    assert_eq!(freq.governor.as_deref(), Some("powersave"));
+    assert_eq!(freq.epp.as_deref(), Some("balance_performance"));
+    assert_eq!(freq.boost, Some(false));
+
+    fs::create_dir_all(sys_cpu.join("cpufreq")).unwrap();
+    fs::write(sys_cpu.join("cpufreq/boost"), "1\n").unwrap();
+    assert_eq!(read_cpufreq(&sys_cpu, 1).boost, Some(true));
+    assert_eq!(read_cpufreq(&sys_cpu, 0), CpuFreq { boost: Some(true), ..CpuFreq::default() });
+
+    assert_eq!(read_vulnerabilities(&sys_cpu.join("vulnerabilities")), vec![
+        ("meltdown".to_string(), "Not affected".to_string()),
+        ("spectre_v2".to_string(), "Mitigation: Enhanced IBRS".to_string()),
+    ]);
+}
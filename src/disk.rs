@@ -1,5 +1,6 @@
-use std::mem;
-use std::path::Path;
+use std::{error, fmt, fs, io, mem};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
 use std::ffi::{CStr, CString};
 
@@ -7,16 +8,230 @@ extern crate libc;
 
 use libc::{c_char, statvfs};
 
+/// statvfs 被信号中断(EINTR)时的最大重试次数
+const STATVFS_RETRY_MAX: usize = 3;
+
+pub const SYS_BLOCK: &str = "/sys/block";
+pub const SYS_CLASS_BLOCK: &str = "/sys/class/block";
+pub const DEV: &str = "/dev";
+pub const DEV_DISK: &str = "/dev/disk";
+
 #[cfg(target_os = "macos")]
-pub fn disk_info(path: &String) -> Result<StatvfsResult, String> {
-    let mut result = call_statvfs(path)?;
+pub fn disk_info(path: &String) -> Result<StatvfsResult, StatvfsError> {
+    let mut result = call_statvfs_retry(path)?;
     result.f_bsize = result.f_frsize;
     Ok(result)
 }
 
 #[cfg(not(target_os = "macos"))]
-pub fn disk_info(path: &String) -> Result<StatvfsResult, String> {
-    call_statvfs(path)
+pub fn disk_info(path: &String) -> Result<StatvfsResult, StatvfsError> {
+    call_statvfs_retry(path)
+}
+
+/// 调用 statvfs，遇到 EINTR 时自动重试，超过 STATVFS_RETRY_MAX 次仍被中断则返回 Interrupted
+fn call_statvfs_retry<S: AsRef<OsStr> + ?Sized>(path: &S) -> Result<StatvfsResult, StatvfsError> {
+    let mut attempt = 0;
+    loop {
+        match call_statvfs(path) {
+            Err(err) if err.is_retryable() && attempt < STATVFS_RETRY_MAX => attempt += 1,
+            result => return result,
+        }
+    }
+}
+
+/// statvfs 调用失败的错误类型，携带路径和 errno
+#[derive(Debug)]
+pub enum StatvfsError {
+    /// ENOENT: 路径不存在
+    NotFound { path: PathBuf, errno: i32 },
+    /// EACCES / EPERM: 没有权限
+    PermissionDenied { path: PathBuf, errno: i32 },
+    /// EINTR: 被信号中断，可以重试
+    Interrupted { path: PathBuf, errno: i32 },
+    /// ESTALE: 失效的文件句柄(通常是断开的 NFS 挂载)
+    Stale { path: PathBuf, errno: i32 },
+    /// 其他错误
+    Other { path: PathBuf, errno: i32 },
+}
+
+impl StatvfsError {
+    pub fn from_errno<P: AsRef<Path>>(path: P, errno: i32) -> Self {
+        let path = path.as_ref().to_path_buf();
+        match errno {
+            libc::ENOENT => StatvfsError::NotFound { path, errno },
+            libc::EACCES | libc::EPERM => StatvfsError::PermissionDenied { path, errno },
+            libc::EINTR => StatvfsError::Interrupted { path, errno },
+            libc::ESTALE => StatvfsError::Stale { path, errno },
+            _ => StatvfsError::Other { path, errno },
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        match self {
+            StatvfsError::NotFound { path, .. }
+            | StatvfsError::PermissionDenied { path, .. }
+            | StatvfsError::Interrupted { path, .. }
+            | StatvfsError::Stale { path, .. }
+            | StatvfsError::Other { path, .. } => path,
+        }
+    }
+
+    pub fn errno(&self) -> i32 {
+        match self {
+            StatvfsError::NotFound { errno, .. }
+            | StatvfsError::PermissionDenied { errno, .. }
+            | StatvfsError::Interrupted { errno, .. }
+            | StatvfsError::Stale { errno, .. }
+            | StatvfsError::Other { errno, .. } => *errno,
+        }
+    }
+
+    /// 只有被信号中断的调用才值得重试
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, StatvfsError::Interrupted { .. })
+    }
+}
+
+impl fmt::Display for StatvfsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let os_err = io::Error::from_raw_os_error(self.errno());
+        write!(f, "Error calling statvfs: path={:?} errno={} err={}", self.path(), self.errno(), os_err)
+    }
+}
+
+impl error::Error for StatvfsError {}
+
+/// 文件系统分类，df 默认不显示伪文件系统和虚拟文件系统
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FsClass {
+    /// 普通文件系统(块设备、网络文件系统等)
+    Regular,
+    /// 内核伪文件系统(proc、sysfs、cgroup ...)，没有实际的存储空间
+    Pseudo,
+    /// 基于内存、叠加或只读镜像的虚拟文件系统(tmpfs、overlay、squashfs ...)
+    Virtual,
+}
+
+/// 根据文件系统类型(Type列)分类
+pub fn fs_class(file_system: &str) -> FsClass {
+    match file_system {
+        "proc" | "sysfs" | "cgroup" | "cgroup2" | "devpts" | "mqueue" | "debugfs" | "tracefs"
+        | "securityfs" | "pstore" | "bpf" | "configfs" | "fusectl" | "hugetlbfs" | "autofs"
+        | "binfmt_misc" | "nsfs" | "rpc_pipefs" | "efivarfs" | "selinuxfs" | "devfs" | "fdescfs" => FsClass::Pseudo,
+        "tmpfs" | "devtmpfs" | "ramfs" | "overlay" | "overlayfs" | "aufs" | "squashfs" | "fuse.lxcfs" => FsClass::Virtual,
+        _ => FsClass::Regular,
+    }
+}
+
+/// 设备的可读名称: loop设备返回后端文件，device-mapper设备返回LVM/crypt名称
+pub fn device_label(name: &str) -> Option<String> {
+    device_label_with(Path::new(DEV), Path::new(SYS_BLOCK), name)
+}
+
+pub fn device_label_with(dev: &Path, sys_block: &Path, name: &str) -> Option<String> {
+    //挂载源通常是 /dev/mapper/vg0-root 等符号链接，先解析到内核设备名，设备节点不存在时按原名查找
+    let kname = kernel_name_with(dev, name).or_else(|| Some(name.strip_prefix("/dev/")?.to_string()))?;
+    if kname.starts_with("loop") {
+        read_sys_string(&sys_block.join(&kname).join("loop/backing_file"))
+    } else if kname.starts_with("dm-") {
+        read_sys_string(&sys_block.join(&kname).join("dm/name"))
+    } else {
+        None
+    }
+}
+
+/// 设备路径对应的内核设备名，例如 /dev/mapper/vg0-root => dm-0
+pub fn kernel_name(name: &str) -> Option<String> {
+    kernel_name_with(Path::new(DEV), name)
+}
+
+/// dev 为设备目录(测试时为伪造的目录)，不在 /dev/ 下的挂载源(tmpfs、overlay等)返回 None
+pub fn kernel_name_with(dev: &Path, name: &str) -> Option<String> {
+    let target = fs::canonicalize(dev.join(name.strip_prefix("/dev/")?)).ok()?;
+    Some(target.file_name()?.to_string_lossy().to_string())
+}
+
+/// 设备的下层设备: 分区返回所在的磁盘，dm/md 等设备返回组成它的 slaves
+pub fn lower_devices(sys_class_block: &Path, kname: &str) -> Vec<String> {
+    let dir = sys_class_block.join(kname);
+    if dir.join("partition").exists() {
+        let parent = fs::canonicalize(&dir).ok()
+            .and_then(|target| Some(target.parent()?.file_name()?.to_string_lossy().to_string()));
+        return parent.into_iter().collect();
+    }
+
+    let mut slaves: Vec<String> = match fs::read_dir(dir.join("slaves")) {
+        Ok(entries) => entries.flatten().map(|entry| entry.file_name().to_string_lossy().to_string()).collect(),
+        Err(_) => Vec::new(),
+    };
+    slaves.sort();
+    slaves
+}
+
+/// 设备所在的物理磁盘: 沿分区和唯一的下层设备向下查找，例如 sda1 => sda，位于 nvme0n1p3 上的 dm-0 => nvme0n1
+/// 跨多个磁盘的设备(md阵列、多盘LVM)本身作为一组
+pub fn physical_device(sys_class_block: &Path, kname: &str) -> String {
+    let lower = lower_devices(sys_class_block, kname);
+    if lower.len() == 1 {
+        physical_device(sys_class_block, &lower[0])
+    } else {
+        kname.to_string()
+    }
+}
+
+/// 读取 /dev/disk/by-uuid、/dev/disk/by-label 等目录，返回 设备真实路径 => 链接名
+pub fn device_links(dir: &Path) -> HashMap<PathBuf, String> {
+    let mut links = HashMap::new();
+    let Ok(entries) = fs::read_dir(dir) else { return links };
+    for entry in entries.flatten() {
+        if let Ok(target) = fs::canonicalize(entry.path()) {
+            let name = unescape_udev(&entry.file_name().to_string_lossy());
+            links.insert(target, name);
+        }
+    }
+    links
+}
+
+/// 查找设备对应的链接名，设备名先解析到真实路径(例如 /dev/mapper/vg-root => /dev/dm-0)
+pub fn device_link(links: &HashMap<PathBuf, String>, name: &str) -> Option<String> {
+    device_link_with(links, Path::new(DEV), name)
+}
+
+/// 不在 /dev/ 下的挂载源(tmpfs、overlay等)直接返回 None，避免按当前目录解析相对路径
+pub fn device_link_with(links: &HashMap<PathBuf, String>, dev: &Path, name: &str) -> Option<String> {
+    let target = fs::canonicalize(dev.join(name.strip_prefix("/dev/")?)).ok()?;
+    links.get(&target).cloned()
+}
+
+/// udev 使用 \xHH 转义链接名中的特殊字符，例如空格为 \x20
+fn unescape_udev(name: &str) -> String {
+    let bytes = name.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 4 <= bytes.len() && bytes[i + 1] == b'x' {
+            let hex = std::str::from_utf8(&bytes[i + 2..i + 4]).ok();
+            if let Some(byte) = hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                out.push(byte);
+                i += 4;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// 读取 sysfs 属性文件，去掉末尾换行，空内容返回 None
+pub(crate) fn read_sys_string(path: &Path) -> Option<String> {
+    let text = fs::read_to_string(path).ok()?;
+    let text = text.trim();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text.to_string())
+    }
 }
 
 #[derive(Debug)]
@@ -36,7 +251,7 @@ pub struct StatvfsResult {
 
 /// 必须要使用C语言风格的Path(以 \0 结尾的字符串)，否则macos不报错，但linux某些情况下会报错。
 /// 注意: std::ffi::CString 不能用于 statvfs，会报错返回-1，用 std::ffi::OsStr 则可以。
-pub fn call_statvfs<S: AsRef<OsStr> + ?Sized>(path: &S) -> Result<StatvfsResult, String> {
+pub fn call_statvfs<S: AsRef<OsStr> + ?Sized>(path: &S) -> Result<StatvfsResult, StatvfsError> {
     let cpath = to_cpath(path);
     let cpath_ptr = cpath.as_ptr() as *const c_char; //as_ptr()必须单独写一句，否则指针会因为中间对象被释放而失效。
     // let cpath_ptr = cpath.as_ptr() as *const _;
@@ -60,17 +275,9 @@ pub fn call_statvfs<S: AsRef<OsStr> + ?Sized>(path: &S) -> Result<StatvfsResult,
             };
             Ok(res)
         } else {//错误处理
-            let os_err = std::io::Error::last_os_error();
-            let raw_os_error = os_err.raw_os_error().unwrap_or_default();
-            let ret_err: String;
-            if os_err.kind() == std::io::ErrorKind::Interrupted {
-                let path = path.as_ref();
-                ret_err = format!("Error calling statvfs: code={result} path={path:?}\nerr: std::io::ErrorKind::Interrupted raw_os_error={raw_os_error} os_err={os_err}");
-            } else {
-                let path = path.as_ref();
-                ret_err = format!("Error calling statvfs: code={result} path={path:?}\nerr: raw_os_error={raw_os_error} os_err={os_err}");
-            }
-            Err(ret_err)
+            let os_err = io::Error::last_os_error();
+            let errno = os_err.raw_os_error().unwrap_or_default();
+            Err(StatvfsError::from_errno(Path::new(path), errno))
         }
     }
 }
@@ -149,6 +356,122 @@ fn test() {
     }
 }
 
+#[test]
+fn test_statvfs_error() {
+    let result = call_statvfs("/path/does/not/exist");
+    match result {
+        Err(err) => {
+            println!("test-Err: {}", err);
+            assert!(matches!(err, StatvfsError::NotFound { .. }));
+            assert_eq!(err.errno(), libc::ENOENT);
+            assert_eq!(err.path(), Path::new("/path/does/not/exist"));
+            assert!(!err.is_retryable());
+        }
+        Ok(res) => panic!("unexpected Ok: {:?}", res),
+    }
+
+    assert!(matches!(StatvfsError::from_errno("/mnt", libc::EACCES), StatvfsError::PermissionDenied { .. }));
+    assert!(matches!(StatvfsError::from_errno("/mnt", libc::ESTALE), StatvfsError::Stale { .. }));
+    assert!(StatvfsError::from_errno("/mnt", libc::EINTR).is_retryable());
+}
+
+#[test]
+fn test_fs_class() {
+    assert_eq!(fs_class("ext4"), FsClass::Regular);
+    assert_eq!(fs_class("nfs4"), FsClass::Regular);
+    assert_eq!(fs_class("proc"), FsClass::Pseudo);
+    assert_eq!(fs_class("cgroup2"), FsClass::Pseudo);
+    assert_eq!(fs_class("tmpfs"), FsClass::Virtual);
+    assert_eq!(fs_class("overlay"), FsClass::Virtual);
+    assert_eq!(fs_class("squashfs"), FsClass::Virtual);
+}
+
+#[test]
+fn test_unescape_udev() {
+    assert_eq!(unescape_udev("my\\x20root"), "my root");
+    assert_eq!(unescape_udev("EFI\\x2fboot"), "EFI/boot");
+    assert_eq!(unescape_udev("1B2C-3D4E"), "1B2C-3D4E");
+    assert_eq!(unescape_udev("bad\\xzz"), "bad\\xzz");
+}
+
+#[test]
+fn test_block_devices() {
+    use std::os::unix::fs::symlink;
+
+    let root = crate::common::test_dir("block-devices");
+    let dev = root.join("dev");
+    fs::create_dir_all(dev.join("mapper")).unwrap();
+    fs::write(dev.join("sda1"), "").unwrap();
+    fs::write(dev.join("dm-0"), "").unwrap();
+    symlink("../dm-0", dev.join("mapper/vg0-root")).unwrap();
+
+    //设备名称: loop 后端文件、device-mapper 名称
+    let sys_block = root.join("block");
+    fs::create_dir_all(sys_block.join("loop3/loop")).unwrap();
+    fs::write(sys_block.join("loop3/loop/backing_file"), "/var/lib/snapd/snaps/core22_1380.snap\n").unwrap();
+    fs::create_dir_all(sys_block.join("dm-0/dm")).unwrap();
+    fs::write(sys_block.join("dm-0/dm/name"), "vg0-root\n").unwrap();
+    fs::create_dir_all(sys_block.join("loop7/loop")).unwrap();
+
+    assert_eq!(device_label_with(&dev, &sys_block, "/dev/loop3"), Some("/var/lib/snapd/snaps/core22_1380.snap".to_string()));
+    assert_eq!(device_label_with(&dev, &sys_block, "/dev/dm-0"), Some("vg0-root".to_string()));
+    assert_eq!(device_label_with(&dev, &sys_block, "/dev/mapper/vg0-root"), Some("vg0-root".to_string()));
+    assert_eq!(kernel_name_with(&dev, "/dev/mapper/vg0-root"), Some("dm-0".to_string()));
+    assert_eq!(device_label_with(&dev, &sys_block, "/dev/mapper/missing"), None);
+    assert_eq!(device_label_with(&dev, &sys_block, "/dev/loop7"), None);
+    assert_eq!(device_label_with(&dev, &sys_block, "/dev/sda1"), None);
+    assert_eq!(device_label_with(&dev, &sys_block, "overlay"), None);
+    assert_eq!(kernel_name_with(&dev, "overlay"), None);
+
+    //物理磁盘: 分区、dm、md 的下层设备
+    let class_block = root.join("class/block");
+    fs::create_dir_all(&class_block).unwrap();
+    for (disk, part) in [("sda", "sda1"), ("sdb", "sdb1"), ("nvme0n1", "nvme0n1p3")] {
+        fs::create_dir_all(root.join("devices").join(disk).join(part)).unwrap();
+        fs::write(root.join("devices").join(disk).join(part).join("partition"), "1\n").unwrap();
+        symlink(format!("../../devices/{disk}"), class_block.join(disk)).unwrap();
+        symlink(format!("../../devices/{disk}/{part}"), class_block.join(part)).unwrap();
+    }
+    fs::create_dir_all(root.join("devices/dm-0/slaves")).unwrap();
+    symlink("../../nvme0n1/nvme0n1p3", root.join("devices/dm-0/slaves/nvme0n1p3")).unwrap();
+    symlink("../../devices/dm-0", class_block.join("dm-0")).unwrap();
+    fs::create_dir_all(root.join("devices/md0/slaves")).unwrap();
+    symlink("../../sda/sda1", root.join("devices/md0/slaves/sda1")).unwrap();
+    symlink("../../sdb/sdb1", root.join("devices/md0/slaves/sdb1")).unwrap();
+    symlink("../../devices/md0", class_block.join("md0")).unwrap();
+
+    assert_eq!(lower_devices(&class_block, "sda1"), vec!["sda".to_string()]);
+    assert_eq!(lower_devices(&class_block, "md0"), vec!["sda1".to_string(), "sdb1".to_string()]);
+    assert_eq!(physical_device(&class_block, "sda1"), "sda");
+    assert_eq!(physical_device(&class_block, "sda"), "sda");
+    assert_eq!(physical_device(&class_block, "dm-0"), "nvme0n1");
+    assert_eq!(physical_device(&class_block, "md0"), "md0");
+    assert_eq!(physical_device(&class_block, "sr0"), "sr0");
+
+    //UUID、卷标: /dev/disk/by-uuid、/dev/disk/by-label 中的链接
+    fs::create_dir_all(root.join("disk/by-uuid")).unwrap();
+    fs::create_dir_all(root.join("disk/by-label")).unwrap();
+    symlink("../../dev/sda1", root.join("disk/by-uuid/1B2C-3D4E")).unwrap();
+    symlink("../../dev/dm-0", root.join("disk/by-uuid/0f6a5c1e-7d1b-4c55-9a0e-2f1d6c0b9e11")).unwrap();
+    symlink("../../dev/dm-0", root.join("disk/by-label/my\\x20root")).unwrap();
+    symlink("../../dev/sdz9", root.join("disk/by-label/dangling")).unwrap();
+
+    let uuids = device_links(&root.join("disk/by-uuid"));
+    let labels = device_links(&root.join("disk/by-label"));
+    assert_eq!(uuids.len(), 2);
+    assert_eq!(labels.len(), 1);
+    assert_eq!(device_link_with(&uuids, &dev, "/dev/sda1"), Some("1B2C-3D4E".to_string()));
+    assert_eq!(device_link_with(&uuids, &dev, "/dev/mapper/vg0-root"), Some("0f6a5c1e-7d1b-4c55-9a0e-2f1d6c0b9e11".to_string()));
+    assert_eq!(device_link_with(&labels, &dev, "/dev/mapper/vg0-root"), Some("my root".to_string()));
+    assert_eq!(device_link_with(&labels, &dev, "/dev/sda1"), None);
+
+    //伪文件系统的挂载源不按当前目录解析
+    fs::write(root.join("overlay"), "").unwrap();
+    symlink("../overlay", root.join("disk/by-uuid/overlay-uuid")).unwrap();
+    let uuids = device_links(&root.join("disk/by-uuid"));
+    assert_eq!(device_link_with(&uuids, &root, "overlay"), None);
+}
+
 #[test]
 fn test_path() {
     let s = String::from("/Users/wyb");
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::disk::read_sys_string;
+
+pub const PROC_INTERRUPTS: &str = "/proc/interrupts";
+pub const PROC_SOFTIRQS: &str = "/proc/softirqs";
+pub const PROC_IRQ: &str = "/proc/irq";
+
+/// /proc/interrupts 或 /proc/softirqs 中的一行: 每个 CPU 的累计次数
+///  24:          1  IO-APIC   5-edge      ACPI:Ged
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct IrqCounts {
+    /// 中断号或名称，例如 24、LOC、NET_RX
+    pub name: String,
+    pub counts: Vec<u64>,
+    /// 中断控制器和设备名称，softirqs 为空
+    pub description: String,
+}
+
+impl IrqCounts {
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+}
+
+/// 解析 /proc/interrupts 和 /proc/softirqs，返回 CPU 名称(表头)和每一行的计数
+pub fn parse_interrupts(text: &str) -> (Vec<String>, Vec<IrqCounts>) {
+    let mut lines = text.lines();
+    let cpus: Vec<String> = lines.next().unwrap_or_default().split_whitespace().map(|cpu| cpu.to_string()).collect();
+
+    let mut irqs = Vec::new();
+    for line in lines {
+        let Some((name, rest)) = line.split_once(':') else { continue };
+        let mut counts = Vec::with_capacity(cpus.len());
+        let mut rest = rest.trim_start();
+        //ERR、MIS 等行只有一个数字，其余行每个 CPU 一列
+        while counts.len() < cpus.len() {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let Ok(count) = rest[..end].parse() else { break };
+            counts.push(count);
+            rest = rest[end..].trim_start();
+        }
+        irqs.push(IrqCounts {
+            name: name.trim().to_string(),
+            counts,
+            description: rest.split_whitespace().collect::<Vec<&str>>().join(" "),
+        });
+    }
+    (cpus, irqs)
+}
+
+/// 两次采样之间每个 CPU 的增量，按名称匹配，新出现的中断从0开始计算
+pub fn diff_interrupts(old: &[IrqCounts], new: &[IrqCounts]) -> Vec<IrqCounts> {
+    let old_map: HashMap<&str, &IrqCounts> = old.iter().map(|irq| (irq.name.as_str(), irq)).collect();
+    let mut deltas = Vec::new();
+    for irq in new {
+        let prev = old_map.get(irq.name.as_str());
+        let counts = irq.counts.iter().enumerate().map(|(index, count)| {
+            let prev = prev.and_then(|prev| prev.counts.get(index)).copied().unwrap_or_default();
+            count.saturating_sub(prev)
+        }).collect();
+        deltas.push(IrqCounts { name: irq.name.clone(), counts, description: irq.description.clone() });
+    }
+    deltas
+}
+
+/// /proc/stat 中的上下文切换次数(ctxt)和中断总数(intr 的第一个数字)
+pub fn parse_stat_counters(text: &str) -> (u64, u64) {
+    let mut ctxt = 0;
+    let mut intr = 0;
+    for line in text.lines() {
+        let mut fields = line.split_whitespace();
+        match fields.next() {
+            Some("ctxt") => ctxt = fields.next().and_then(|v| v.parse().ok()).unwrap_or_default(),
+            Some("intr") => intr = fields.next().and_then(|v| v.parse().ok()).unwrap_or_default(),
+            _ => {}
+        }
+    }
+    (ctxt, intr)
+}
+
+/// 中断的 CPU 亲和性，例如 0-3，只有数字编号的中断才有
+pub fn irq_affinity(proc_irq: &Path, name: &str) -> Option<String> {
+    if !name.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    read_sys_string(&proc_irq.join(name).join("smp_affinity_list"))
+}
+
+pub fn read_interrupts(path: &Path) -> (Vec<String>, Vec<IrqCounts>) {
+    fs::read_to_string(path).map(|text| parse_interrupts(&text)).unwrap_or_default()
+}
+
+#[cfg(test)]
+const INTERRUPTS: &str = "           CPU0       CPU1
+  0:         38          0   IO-APIC   2-edge      timer
+ 24:        100        900  PCI-MSI 327680-edge      xhci_hcd
+ 28:       5000          2  PCI-MSIX-0000:00:01.0   0-edge      virtio0-input.0
+LOC:      26461      25000   Local timer interrupts
+ERR:          0
+";
+
+#[test]
+fn test_parse_interrupts() {
+    let (cpus, irqs) = parse_interrupts(INTERRUPTS);
+    assert_eq!(cpus, vec!["CPU0", "CPU1"]);
+    assert_eq!(irqs.len(), 5);
+    assert_eq!(irqs[1].name, "24");
+    assert_eq!(irqs[1].counts, vec![100, 900]);
+    assert_eq!(irqs[1].description, "PCI-MSI 327680-edge xhci_hcd");
+    assert_eq!(irqs[3].name, "LOC");
+    assert_eq!(irqs[3].total(), 51461);
+    assert_eq!(irqs[4].counts, vec![0]);
+    assert!(irqs[4].description.is_empty());
+
+    let (cpus, softirqs) = parse_interrupts("                    CPU0       CPU1\n          HI:          0          1\n      NET_RX:       2455         10\n");
+    assert_eq!(cpus.len(), 2);
+    assert_eq!(softirqs[1].name, "NET_RX");
+    assert_eq!(softirqs[1].counts, vec![2455, 10]);
+}
+
+#[test]
+fn test_diff_interrupts() {
+    let (_, old) = parse_interrupts(INTERRUPTS);
+    let mut new = old.clone();
+    new[2].counts = vec![5600, 2];
+    new.push(IrqCounts { name: "29".to_string(), counts: vec![3, 4], description: String::new() });
+
+    let deltas = diff_interrupts(&old, &new);
+    assert_eq!(deltas[2].counts, vec![600, 0]);
+    assert_eq!(deltas[0].total(), 0);
+    assert_eq!(deltas[5].counts, vec![3, 4]);
+}
+
+#[test]
+fn test_parse_stat_counters() {
+    let stat = "cpu  1 2 3 4\nintr 123456 38 0 0\nctxt 987654\nbtime 1700000000\n";
+    assert_eq!(parse_stat_counters(stat), (987654, 123456));
+}
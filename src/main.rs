@@ -1,12 +1,23 @@
+mod cgroup;
 mod common;
+mod cpu;
 mod disk;
+mod irq;
+mod md;
+mod memory;
+mod mount;
+mod pressure;
+mod process;
 mod sys_info;
 mod table;
 
+use std::ffi::{OsStr, OsString};
 use std::fmt::Debug;
-use clap::{ArgAction, Parser, Subcommand};
+use std::path::Path;
+use clap::{ArgAction, Args, Parser, Subcommand};
 
 use sys_info::SysInfo;
+use table::Thresholds;
 
 #[derive(Debug, Parser)] // requires `derive` feature
 #[command(name = "sysinfo", bin_name = "sysinfo")]
@@ -28,6 +39,10 @@ struct Cli {
     #[arg(short = 'c', long)]
     no_color: bool,
 
+    /// Color Use% green below WARN, yellow below CRIT and red otherwise [default: 70,90, pressure: 10,40]
+    #[arg(long, value_name = "WARN,CRIT", global = true)]
+    thresholds: Option<String>,
+
     //[custom_version_flag](https://github.com/clap-rs/clap/blob/v4.4.18/tests/derive/help.rs#L446)
     /// Print version
     #[arg(short = 'v', long = "version", action = ArgAction::Version, value_parser = clap::value_parser ! (bool))]
@@ -48,18 +63,34 @@ pub enum Commands {
     System {},
 
     /// Print cpu info
-    Cpu {
-        /// Print cpu details
-        #[arg(short, long)]
-        details: bool,
-    },
+    Cpu(CpuArgs),
 
     /// Print memory and swap info
-    Memory {},
+    #[command(after_help = "The SIZE argument is an integer and optional unit (example: 10K is 10*1024 or 10K is 10*1000).
+Units are B,K,M,G,T,P,E,Z,Y (powers of 1024 [-h | --human_readable] or 1000 [-H | --si]).
+")]
+    Memory(MemoryArgs),
+
+    /// Print software RAID (md) status
+    Md {},
+
+    /// Print pressure stall information (PSI) for cpu, memory and io
+    Pressure {},
+
+    /// Print interrupt rates per CPU and per IRQ source, softirqs and context switches
+    Irq {
+        /// Time between the two samples (e.g., 500ms, 2s)
+        #[arg(short, long, value_name = "DURATION", default_value_t = String::from("1s"))]
+        interval: String,
+
+        /// Print all interrupt sources, including idle ones
+        #[arg(short, long)]
+        all: bool,
+    },
 
     /// Print disk info
     #[command(after_help = "FIELD is a column to be included.  Valid field names are:
-[Device | Type | Kind | Total | Used | Free | Avail | Use% | MountPoint | Removable] (see info page).
+[Device | Type | Kind | Total | Used | Free | Avail | Use% | Usage | Deleted | MountPoint | Removable | UUID | Label] (see info page).
 
 The SIZE argument is an integer and optional unit (example: 10K is 10*1024 or 10K is 10*1000).
 Units are B,K,M,G,T,P,E,Z,Y (powers of 1024 [-h | --human_readable] or 1000 [-H | --si]).
@@ -68,7 +99,7 @@ Additional help information can be found here. (https://github.com/LonelyPale/sy
 ")] //自定义help后输出的内容，使用属性宏clap和command都可以
     //Units are K,M,G,T,P,E,Z,Y (powers of 1024) or KB,MB,... (powers of 1000).
     Disk {
-        /// Print all fields
+        /// Print all fields and all file systems, including pseudo, virtual and duplicate ones
         #[arg(short, long)]
         all: bool,
 
@@ -85,6 +116,10 @@ Additional help information can be found here. (https://github.com/LonelyPale/sy
         #[arg(short, long)]
         total: bool,
 
+        /// Group rows by field with a subtotal per group; 'device' groups partitions by physical disk; see FIELD format below
+        #[arg(short, long, value_name = "FIELD", default_value_t = String::from(""))]
+        group_by: String,
+
         /// Print sizes in powers of 1024 (e.g., 1023M) [default: true]
         #[arg(short = 'h', long)]
         human_readable: bool,
@@ -98,31 +133,298 @@ Additional help information can be found here. (https://github.com/LonelyPale/sy
         /// see SIZE format below
         #[arg(short = 'B', long, value_name = "SIZE", default_value_t = String::from(""))]
         block_size: String,
+
+        /// Print Use% as a bar graph; STYLE is ascii or unicode
+        #[arg(long, value_name = "STYLE", num_args = 0..=1, default_missing_value = "ascii", default_value_t = String::from(""))]
+        bar: String,
+
+        /// Watch /proc/self/mountinfo and print added, removed and remounted file systems
+        #[arg(long, conflicts_with_all = ["busy", "deleted"])]
+        watch_mounts: bool,
+
+        /// Print --watch-mounts events as JSON lines
+        #[arg(long, requires = "watch_mounts")]
+        json: bool,
+
+        /// List processes keeping MOUNTPOINT busy (open files, cwd, root and memory maps)
+        #[arg(long, value_name = "MOUNTPOINT", default_value_t = String::from(""), conflicts_with = "deleted")]
+        busy: String,
+
+        /// Show space held by deleted-but-open files per mount point, and the processes holding them
+        #[arg(long)]
+        deleted: bool,
     },
 }
 
+#[derive(Debug, Args)]
+pub struct CpuArgs {
+    /// Print cpu details
+    #[arg(short, long)]
+    pub details: bool,
+
+    /// Print per-core usage as a bar graph; STYLE is ascii or unicode
+    #[arg(long, value_name = "STYLE", num_args = 0..=1, default_missing_value = "ascii", default_value_t = String::from(""))]
+    pub bar: String,
+
+    /// Generate avg, min and max rows for cpu details
+    #[arg(short, long)]
+    pub total: bool,
+
+    /// Time between CPU usage samples (e.g., 500ms, 2s); never shorter than the minimum update interval
+    #[arg(short, long, value_name = "DURATION", default_value_t = String::from(""))]
+    pub interval: String,
+
+    /// Number of samples to take; reports the average, min and max usage per core
+    #[arg(short = 'n', long, value_name = "N", default_value_t = 1)]
+    pub samples: u32,
+
+    /// Print the share of time spent in each CPU state (user, system, iowait, steal, ...) per core, from /proc/stat
+    #[arg(long, conflicts_with_all = ["topology", "features", "details", "bar", "total", "samples"])]
+    pub breakdown: bool,
+
+    /// Print sockets, physical cores, SMT siblings, NUMA nodes and cache sizes per logical CPU
+    #[arg(long, conflicts_with_all = ["features", "details", "bar", "total", "interval", "samples"])]
+    pub topology: bool,
+
+    /// Print instruction set flags grouped by family (AVX, AES, SHA, ...) and vulnerability mitigations
+    #[arg(long, conflicts_with_all = ["details", "bar", "total", "interval", "samples"])]
+    pub features: bool,
+}
+
+impl Default for CpuArgs {
+    fn default() -> Self {
+        Self {
+            details: false,
+            bar: String::new(),
+            total: false,
+            interval: String::new(),
+            samples: 1,
+            breakdown: false,
+            topology: false,
+            features: false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Args)]
+pub struct MemoryArgs {
+    /// Print Use% as a bar graph; STYLE is ascii or unicode
+    #[arg(long, value_name = "STYLE", num_args = 0..=1, default_missing_value = "ascii", default_value_t = String::from(""))]
+    pub bar: String,
+
+    /// Print the /proc/meminfo breakdown: buffers, cache, slab, dirty, commit and more
+    #[arg(short, long)]
+    pub details: bool,
+
+    /// Print sizes in powers of 1024 (e.g., 1023M) [default: true]
+    #[arg(short = 'h', long, visible_alias = "human")]
+    pub human_readable: bool,
+
+    /// Print sizes in powers of 1000 (e.g., 1.1G) Metric (SI) Prefixes
+    #[arg(short = 'H', long)]
+    pub si: bool,
+
+    /// Scale sizes by SIZE before printing them;
+    /// e.g., '-BM' prints sizes in units of 1,048,576 bytes;
+    /// see SIZE format below
+    #[arg(short = 'B', long, value_name = "SIZE", default_value_t = String::from(""))]
+    pub block_size: String,
+
+    /// Print the N processes using the most swap (VmSwap)
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub swap_top: usize,
+
+    /// Print in the layout of procps free(1); also used when the binary is invoked as free.
+    /// Not supported: -L/--line
+    #[arg(long)]
+    pub free: bool,
+
+    /// With --free: show output in bytes
+    #[arg(short = 'b', long, requires = "free")]
+    pub bytes: bool,
+
+    /// With --free: show output in kibibytes [default]
+    #[arg(short = 'k', long, requires = "free")]
+    pub kibi: bool,
+
+    /// With --free: show output in mebibytes
+    #[arg(short = 'm', long, requires = "free")]
+    pub mebi: bool,
+
+    /// With --free: show output in gibibytes
+    #[arg(short = 'g', long, requires = "free")]
+    pub gibi: bool,
+
+    /// With --free: show output in tebibytes
+    #[arg(long, requires = "free")]
+    pub tebi: bool,
+
+    /// With --free: show output in pebibytes
+    #[arg(long, requires = "free")]
+    pub pebi: bool,
+
+    /// With --free: show output in kilobytes
+    #[arg(long, requires = "free")]
+    pub kilo: bool,
+
+    /// With --free: show output in megabytes
+    #[arg(long, requires = "free")]
+    pub mega: bool,
+
+    /// With --free: show output in gigabytes
+    #[arg(long, requires = "free")]
+    pub giga: bool,
+
+    /// With --free: show output in terabytes
+    #[arg(long, requires = "free")]
+    pub tera: bool,
+
+    /// With --free: show output in petabytes
+    #[arg(long, requires = "free")]
+    pub peta: bool,
+
+    /// With --free: show buffers and cache in separate columns
+    #[arg(short = 'w', long, requires = "free")]
+    pub wide: bool,
+
+    /// With --free: show a total line for memory and swap
+    #[arg(short = 't', long, requires = "free")]
+    pub total: bool,
+
+    /// With --free: show detailed low and high memory statistics
+    #[arg(short = 'l', long, requires = "free")]
+    pub lohi: bool,
+
+    /// With --free: show committed memory and commit limit (-v when invoked as free)
+    #[arg(long, requires = "free")]
+    pub committed: bool,
+
+    /// With --free: repeat printing every DURATION (e.g., 2, 500ms)
+    #[arg(short = 's', long, value_name = "DURATION", requires = "free", default_value_t = String::from(""))]
+    pub seconds: String,
+
+    /// With --free: repeat printing N times, then exit
+    #[arg(short = 'c', long, value_name = "N", requires = "free", default_value_t = 0)]
+    pub count: u32,
+}
+
+/// 以 free 运行时的帮助信息，与 procps-ng free 的选项一致
+const FREE_HELP: &str = "
+Usage:
+ free [options]
+
+Options:
+ -b, --bytes         show output in bytes
+     --kilo          show output in kilobytes
+     --mega          show output in megabytes
+     --giga          show output in gigabytes
+     --tera          show output in terabytes
+     --peta          show output in petabytes
+ -k, --kibi          show output in kibibytes
+ -m, --mebi          show output in mebibytes
+ -g, --gibi          show output in gibibytes
+     --tebi          show output in tebibytes
+     --pebi          show output in pebibytes
+ -h, --human         show human-readable output
+     --si            use powers of 1000 not 1024
+ -l, --lohi          show detailed low and high memory statistics
+ -t, --total         show total for RAM + swap
+ -v, --committed     show committed memory and commit limit
+ -s N, --seconds N   repeat printing every N seconds
+ -c N, --count N     repeat printing N times, then exit
+ -w, --wide          wide output
+
+     --help     display this help and exit
+ -V, --version  output version information and exit
+
+Not supported: -L, --line
+";
+
+/// free 的参数转换为 memory --free 的参数: 拆开合并的短选项(-tw)，-v 对应 --committed
+/// --help 和 -V 输出后直接退出
+fn free_args(args: &[OsString]) -> Vec<OsString> {
+    let mut expanded = Vec::new();
+    for arg in args {
+        match arg.to_str() {
+            Some(group) if group.len() > 2 && group.starts_with('-') && !group.starts_with("--")
+                && group[1..].chars().all(|c| "bkmghltvwV".contains(c)) => {
+                expanded.extend(group[1..].chars().map(|c| OsString::from(format!("-{c}"))));
+            }
+            _ => expanded.push(arg.clone()),
+        }
+    }
+
+    let mut out = vec![OsString::from("memory"), OsString::from("--free")];
+    for arg in expanded {
+        match arg.to_str() {
+            Some("--help") => {
+                print!("{FREE_HELP}");
+                std::process::exit(0);
+            }
+            Some("-V" | "--version") => {
+                println!("free from {} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+                std::process::exit(0);
+            }
+            Some("-L" | "--line") => {
+                eprintln!("free: -L/--line is not supported");
+                std::process::exit(1);
+            }
+            Some("-v") => out.push(OsString::from("--committed")),
+            _ => out.push(arg),
+        }
+    }
+    out
+}
+
 fn main() {
-    let args = Cli::parse();
+    //以 free 的名称运行(例如软链接 free -> sysinfo)时，等同于 sysinfo memory --free
+    let mut argv: Vec<OsString> = std::env::args_os().collect();
+    let program = argv.first().map(Path::new).and_then(|path| path.file_name()).map(|name| name.to_os_string());
+    if program.as_deref() == Some(OsStr::new("free")) {
+        let args = free_args(&argv[1..]);
+        argv.splice(1.., args);
+    }
+    let args = Cli::parse_from(argv);
 
     if args.no_color {
         colored::control::set_override(false);
     }
 
+    //PSI 的百分比通常远低于使用率，pressure 使用更低的默认阈值
+    let default_thresholds = if matches!(args.command, Some(Commands::Pressure {})) { "10,40" } else { "70,90" };
+    let thresholds_arg = args.thresholds.as_deref().unwrap_or(default_thresholds);
+    let thresholds: Thresholds = match thresholds_arg.parse() {
+        Ok(val) => val,
+        Err(err) => {
+            eprintln!("{err}: {thresholds_arg}");
+            return;
+        }
+    };
+
     match args.command {
         Some(Commands::System {}) => {
             SysInfo::new().print_system();
         }
-        Some(Commands::Cpu { details }) => {
-            SysInfo::new_cpu().print_cpu(details);
+        Some(Commands::Cpu(cpu_args)) => {
+            SysInfo::new_cpu().with_thresholds(thresholds).print_cpu(cpu_args);
+        }
+        Some(Commands::Memory(memory_args)) => {
+            SysInfo::new_memory().with_thresholds(thresholds).print_memory(memory_args);
+        }
+        Some(Commands::Md {}) => {
+            SysInfo::new().print_md();
+        }
+        Some(Commands::Pressure {}) => {
+            SysInfo::new().with_thresholds(thresholds).print_pressure();
         }
-        Some(Commands::Memory {}) => {
-            SysInfo::new_memory().print_memory();
+        Some(Commands::Irq { interval, all }) => {
+            SysInfo::new().print_irq(&interval, all);
         }
         Some(Commands::Disk { .. }) => {
-            SysInfo::new().print_disk(args.command.unwrap());
+            SysInfo::new().with_thresholds(thresholds).print_disk(args.command.unwrap());
         }
         None => {
-            SysInfo::new_all().print_all();
+            SysInfo::new_all().with_thresholds(thresholds).print_all();
         }
         // _ => {
         //     println!("testing...");
@@ -0,0 +1,275 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use crate::disk::{lower_devices, read_sys_string};
+
+pub const PROC_MDSTAT: &str = "/proc/mdstat";
+
+/// 阵列成员设备，例如 sdb1[1](F)
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MdMember {
+    pub name: String,
+    pub index: u32,
+    /// (F) 故障
+    pub faulty: bool,
+    /// (S) 备用盘
+    pub spare: bool,
+}
+
+/// 软RAID阵列状态，来自 /proc/mdstat 和 /sys/block/md*/md/
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MdArray {
+    pub name: String,
+    pub active: bool,
+    pub read_only: bool,
+    pub level: String,
+    pub members: Vec<MdMember>,
+    /// [2/1] 中的 2: 阵列应有的磁盘数
+    pub raid_disks: u32,
+    /// [2/1] 中的 1: 正常工作的磁盘数
+    pub active_disks: u32,
+    /// 例如 [U_]
+    pub status: String,
+    /// recovery / resync / check / reshape
+    pub sync_action: Option<String>,
+    /// 同步进度百分比，DELAYED/PENDING 时为 None
+    pub sync_progress: Option<f64>,
+    pub degraded: bool,
+}
+
+impl MdMember {
+    /// 解析 sdb1[1](F) 格式的成员
+    fn parse(token: &str) -> Option<Self> {
+        let open = token.find('[')?;
+        let close = token.find(']')?;
+        let name = token[..open].to_string();
+        let index: u32 = token[open + 1..close].parse().ok()?;
+        let flags = &token[close + 1..];
+        Some(Self {
+            name,
+            index,
+            faulty: flags.contains("(F)"),
+            spare: flags.contains("(S)"),
+        })
+    }
+}
+
+impl MdArray {
+    /// 成员列表，例如 sda1[0] sdb1[1](F)
+    pub fn members_text(&self) -> String {
+        let members: Vec<String> = self.members.iter().map(|m| {
+            let mut text = format!("{}[{}]", m.name, m.index);
+            if m.faulty {
+                text.push_str("(F)");
+            }
+            if m.spare {
+                text.push_str("(S)");
+            }
+            text
+        }).collect();
+        members.join(" ")
+    }
+
+    /// 同步状态，例如 recovery 8.50%
+    pub fn sync_text(&self) -> String {
+        match (&self.sync_action, self.sync_progress) {
+            (Some(action), Some(progress)) => format!("{action} {progress:.2}%"),
+            (Some(action), None) => action.clone(),
+            _ => String::new(),
+        }
+    }
+}
+
+/// 读取全部阵列，不支持md(没有 /proc/mdstat)时返回空列表
+pub fn read_md_arrays(proc_mdstat: &Path, sys_block: &Path) -> Vec<MdArray> {
+    let Ok(text) = fs::read_to_string(proc_mdstat) else { return Vec::new() };
+    let mut arrays = parse_mdstat(&text);
+    for array in arrays.iter_mut() {
+        apply_sysfs(array, &sys_block.join(&array.name).join("md"));
+    }
+    arrays
+}
+
+/// 解析 /proc/mdstat
+pub fn parse_mdstat(text: &str) -> Vec<MdArray> {
+    let mut arrays: Vec<MdArray> = Vec::new();
+    for line in text.lines() {
+        if line.starts_with("md") {
+            let Some((name, rest)) = line.split_once(" : ") else { continue };
+            let mut array = MdArray {
+                name: name.trim().to_string(),
+                ..MdArray::default()
+            };
+            for token in rest.split_whitespace() {
+                match token {
+                    "active" => array.active = true,
+                    "inactive" => array.active = false,
+                    "(read-only)" | "(auto-read-only)" => array.read_only = true,
+                    _ if token.contains('[') => {
+                        if let Some(member) = MdMember::parse(token) {
+                            array.members.push(member);
+                        }
+                    }
+                    _ if array.level.is_empty() => array.level = token.to_string(),
+                    _ => {}
+                }
+            }
+            arrays.push(array);
+        } else if line.starts_with(char::is_whitespace) {
+            let Some(array) = arrays.last_mut() else { continue };
+            parse_detail_line(array, line.trim());
+        }
+    }
+
+    for array in arrays.iter_mut() {
+        array.degraded = array.active_disks < array.raid_disks || array.members.iter().any(|m| m.faulty);
+    }
+    arrays
+}
+
+/// 解析阵列的明细行:
+/// 1953382464 blocks super 1.2 [2/1] [U_]
+/// [=>...................]  recovery =  8.5% (166208/1953382464) finish=162.1min speed=200000K/sec
+/// resync=DELAYED
+fn parse_detail_line(array: &mut MdArray, line: &str) {
+    for action in ["recovery", "resync", "check", "reshape", "repair"] {
+        if let Some(pos) = line.find(action) {
+            let rest = line[pos + action.len()..].trim_start();
+            if let Some(rest) = rest.strip_prefix('=') {
+                array.sync_action = Some(action.to_string());
+                let value = rest.split_whitespace().next().unwrap_or_default();
+                match value.strip_suffix('%') {
+                    Some(percent) => array.sync_progress = percent.parse().ok(),
+                    None => array.sync_action = Some(format!("{action} {}", value.to_lowercase())),
+                }
+                return;
+            }
+        }
+    }
+
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    for (index, token) in tokens.iter().enumerate() {
+        let Some(inner) = token.strip_prefix('[').and_then(|t| t.strip_suffix(']')) else { continue };
+        let Some((raid, active)) = inner.split_once('/') else { continue };
+        let (Ok(raid), Ok(active)) = (raid.parse(), active.parse()) else { continue };
+        array.raid_disks = raid;
+        array.active_disks = active;
+        if let Some(status) = tokens.get(index + 1) {
+            array.status = status.to_string();
+        }
+        return;
+    }
+}
+
+/// 使用 /sys/block/mdX/md/ 中的信息补充 mdstat
+fn apply_sysfs(array: &mut MdArray, md_dir: &Path) {
+    if let Some(degraded) = read_sys_string(&md_dir.join("degraded")) {
+        if degraded.parse::<u32>().unwrap_or_default() > 0 {
+            array.degraded = true;
+        }
+    }
+    if array.level.is_empty() {
+        if let Some(level) = read_sys_string(&md_dir.join("level")) {
+            array.level = level;
+        }
+    }
+}
+
+/// 查找设备所依赖的降级阵列: 设备本身、分区所在的磁盘或 dm 的下层设备
+pub fn degraded_array(sys_class_block: &Path, kname: &str, degraded: &HashSet<String>) -> Option<String> {
+    if degraded.contains(kname) {
+        return Some(kname.to_string());
+    }
+    for lower in lower_devices(sys_class_block, kname) {
+        if let Some(md) = degraded_array(sys_class_block, &lower, degraded) {
+            return Some(md);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+const MDSTAT: &str = "Personalities : [raid1] [raid6] [raid5] [raid4]
+md1 : active raid5 sdd1[3] sdc1[1] sdb1[0]
+      3906764800 blocks super 1.2 level 5, 512k chunk, algorithm 2 [3/2] [UU_]
+      [=>...................]  recovery =  8.5% (166208/1953382400) finish=162.1min speed=200000K/sec
+      bitmap: 2/15 pages [8KB], 65536KB chunk
+
+md0 : active raid1 sdb2[1](F) sda2[0]
+      1953382464 blocks super 1.2 [2/1] [U_]
+      resync=DELAYED
+
+md2 : active (auto-read-only) raid1 sdf1[1] sde1[0] sdg1[2](S)
+      976630464 blocks super 1.2 [2/2] [UU]
+
+md127 : inactive sdh[0](S)
+      976762584 blocks super 1.2
+
+unused devices: <none>
+";
+
+#[test]
+fn test_parse_mdstat() {
+    let arrays = parse_mdstat(MDSTAT);
+    assert_eq!(arrays.len(), 4);
+
+    let md1 = &arrays[0];
+    assert_eq!(md1.name, "md1");
+    assert!(md1.active);
+    assert_eq!(md1.level, "raid5");
+    assert_eq!(md1.members.len(), 3);
+    assert_eq!((md1.raid_disks, md1.active_disks), (3, 2));
+    assert_eq!(md1.status, "[UU_]");
+    assert_eq!(md1.sync_text(), "recovery 8.50%");
+    assert!(md1.degraded);
+
+    let md0 = &arrays[1];
+    assert_eq!(md0.members_text(), "sdb2[1](F) sda2[0]");
+    assert_eq!(md0.sync_text(), "resync delayed");
+    assert!(md0.degraded);
+
+    let md2 = &arrays[2];
+    assert!(md2.read_only);
+    assert_eq!(md2.level, "raid1");
+    assert!(md2.members[2].spare);
+    assert!(!md2.degraded);
+
+    let md127 = &arrays[3];
+    assert!(!md127.active);
+    assert!(md127.level.is_empty());
+    assert!(!md127.degraded);
+}
+
+#[test]
+fn test_degraded_array() {
+    use std::os::unix::fs::symlink;
+
+    let root = crate::common::test_dir("md-degraded");
+    let class_block = root.join("class/block");
+    fs::create_dir_all(&class_block).unwrap();
+    fs::create_dir_all(root.join("devices/md0/md0p1")).unwrap();
+    fs::write(root.join("devices/md0/md0p1/partition"), "1\n").unwrap();
+    fs::create_dir_all(root.join("devices/dm-0/slaves")).unwrap();
+    fs::create_dir_all(root.join("devices/sda/sda1")).unwrap();
+    fs::write(root.join("devices/sda/sda1/partition"), "1\n").unwrap();
+    symlink("../../devices/md0", class_block.join("md0")).unwrap();
+    symlink("../../devices/md0/md0p1", class_block.join("md0p1")).unwrap();
+    symlink("../../devices/dm-0", class_block.join("dm-0")).unwrap();
+    symlink("../../devices/sda/sda1", class_block.join("sda1")).unwrap();
+    symlink("../../../md0/md0p1", root.join("devices/dm-0/slaves/md0p1")).unwrap();
+
+    let degraded = HashSet::from(["md0".to_string()]);
+    assert_eq!(degraded_array(&class_block, "md0", &degraded), Some("md0".to_string()));
+    assert_eq!(degraded_array(&class_block, "md0p1", &degraded), Some("md0".to_string()));
+    assert_eq!(degraded_array(&class_block, "dm-0", &degraded), Some("md0".to_string()));
+    assert_eq!(degraded_array(&class_block, "sda1", &degraded), None);
+
+    let proc_mdstat = root.join("mdstat");
+    fs::write(&proc_mdstat, MDSTAT).unwrap();
+    fs::create_dir_all(root.join("block/md2/md")).unwrap();
+    fs::write(root.join("block/md2/md/degraded"), "1\n").unwrap();
+    let arrays = read_md_arrays(&proc_mdstat, &root.join("block"));
+    assert!(arrays[2].degraded);
+    assert!(read_md_arrays(&root.join("missing"), &root.join("block")).is_empty());
+}
@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+pub const PROC_MEMINFO: &str = "/proc/meminfo";
+pub const PROC_SWAPS: &str = "/proc/swaps";
+
+/// memory --details 显示的 /proc/meminfo 字段和说明
+pub const MEMINFO_DETAILS: [(&str, &str); 13] = [
+    ("Buffers", "Block device buffers"),
+    ("Cached", "Page cache, reclaimable"),
+    ("Shmem", "Shared memory and tmpfs, counted in Cached"),
+    ("Slab", "Kernel slab caches"),
+    ("SReclaimable", "Reclaimable slab (dentries, inodes)"),
+    ("SUnreclaim", "Unreclaimable slab"),
+    ("Dirty", "Waiting to be written back to disk"),
+    ("Writeback", "Being written back to disk"),
+    ("Mapped", "Files mapped into memory"),
+    ("AnonPages", "Anonymous application memory"),
+    ("KernelStack", "Kernel stacks"),
+    ("Committed_AS", "Memory allocated by processes"),
+    ("CommitLimit", "Allocation limit under strict overcommit"),
+];
+
+/// 解析 /proc/meminfo，单位转换为字节，没有单位的字段(HugePages_Total 等)保持原值
+/// MemTotal:        6153852 kB
+pub fn parse_meminfo(text: &str) -> HashMap<String, u64> {
+    let mut meminfo = HashMap::new();
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let mut fields = value.split_whitespace();
+        let Some(Ok(number)) = fields.next().map(|v| v.parse::<u64>()) else { continue };
+        let number = match fields.next() {
+            Some("kB") => number * 1024,
+            _ => number,
+        };
+        meminfo.insert(key.trim().to_string(), number);
+    }
+    meminfo
+}
+
+pub fn read_meminfo(proc_meminfo: &Path) -> HashMap<String, u64> {
+    fs::read_to_string(proc_meminfo).map(|text| parse_meminfo(&text)).unwrap_or_default()
+}
+
+/// /proc/swaps 中的交换设备，大小单位为字节
+/// /dev/sda2    partition    8388604    1024    -2
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SwapDevice {
+    pub name: String,
+    /// partition / file
+    pub kind: String,
+    pub size: u64,
+    pub used: u64,
+    pub priority: i32,
+}
+
+/// 解析 /proc/swaps，跳过表头
+pub fn parse_swaps(text: &str) -> Vec<SwapDevice> {
+    let mut devices = Vec::new();
+    for line in text.lines().skip(1) {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 5 {
+            continue;
+        }
+        let (Ok(size), Ok(used), Ok(priority)) = (fields[2].parse::<u64>(), fields[3].parse::<u64>(), fields[4].parse()) else { continue };
+        devices.push(SwapDevice {
+            //路径中的空格转义为 \040
+            name: fields[0].replace("\\040", " "),
+            kind: fields[1].to_string(),
+            size: size * 1024,
+            used: used * 1024,
+            priority,
+        });
+    }
+    devices
+}
+
+pub fn read_swaps(proc_swaps: &Path) -> Vec<SwapDevice> {
+    fs::read_to_string(proc_swaps).map(|text| parse_swaps(&text)).unwrap_or_default()
+}
+
+/// free(1) 显示的数值，单位为字节，计算方法与 procps-ng 4 相同
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct FreeStats {
+    pub mem_total: u64,
+    pub mem_used: u64,
+    pub mem_free: u64,
+    pub shared: u64,
+    pub buffers: u64,
+    /// Cached + SReclaimable
+    pub cache: u64,
+    pub available: u64,
+    pub swap_total: u64,
+    pub swap_used: u64,
+    pub swap_free: u64,
+    /// 低端/高端内存(-l)，64位系统没有 LowTotal/HighTotal，低端内存即全部内存
+    pub low_total: u64,
+    pub low_free: u64,
+    pub high_total: u64,
+    pub high_free: u64,
+    /// CommitLimit 和 Committed_AS(-v)
+    pub commit_limit: u64,
+    pub committed: u64,
+}
+
+impl FreeStats {
+    pub fn from_meminfo(meminfo: &HashMap<String, u64>) -> Self {
+        let get = |key: &str| meminfo.get(key).copied().unwrap_or_default();
+        let mem_total = get("MemTotal");
+        let mem_free = get("MemFree");
+        let cache = get("Cached") + get("SReclaimable");
+        //旧内核没有 MemAvailable，用 free + buff/cache 近似
+        let available = meminfo.get("MemAvailable").copied().unwrap_or(mem_free + get("Buffers") + cache).min(mem_total);
+        let swap_total = get("SwapTotal");
+        let swap_free = get("SwapFree");
+        Self {
+            mem_total,
+            mem_used: mem_total - available,
+            mem_free,
+            shared: get("Shmem"),
+            buffers: get("Buffers"),
+            cache,
+            available,
+            swap_total,
+            swap_used: swap_total.saturating_sub(swap_free),
+            swap_free,
+            low_total: meminfo.get("LowTotal").copied().unwrap_or(mem_total),
+            low_free: meminfo.get("LowFree").copied().unwrap_or(mem_free),
+            high_total: get("HighTotal"),
+            high_free: get("HighFree"),
+            commit_limit: get("CommitLimit"),
+            committed: get("Committed_AS"),
+        }
+    }
+}
+
+/// free 的显示单位: -b/-k/-m/-g/--tebi/--pebi/-h，--kilo 等1000进制单位为对应单位加 --si
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum FreeUnit {
+    Bytes,
+    #[default]
+    Kibi,
+    Mebi,
+    Gibi,
+    Tebi,
+    Pebi,
+    Human,
+}
+
+/// free 的显示选项
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct FreeOptions {
+    pub unit: FreeUnit,
+    /// 使用1000进制
+    pub si: bool,
+    /// buffers 和 cache 分两列显示
+    pub wide: bool,
+    /// 内存和交换分区的合计行
+    pub total: bool,
+    /// Low/High 行
+    pub lohi: bool,
+    /// Comm 行
+    pub committed: bool,
+}
+
+/// 格式化一个数值，--si 时使用1000进制
+pub fn free_size(bytes: u64, unit: FreeUnit, si: bool) -> String {
+    let base: u64 = if si { 1000 } else { 1024 };
+    match unit {
+        FreeUnit::Bytes => bytes.to_string(),
+        FreeUnit::Kibi => (bytes / base).to_string(),
+        FreeUnit::Mebi => (bytes / base / base).to_string(),
+        FreeUnit::Gibi => (bytes / base / base / base).to_string(),
+        FreeUnit::Tebi => (bytes / base / base / base / base).to_string(),
+        FreeUnit::Pebi => (bytes / base / base / base / base / base).to_string(),
+        FreeUnit::Human => {
+            if bytes < base {
+                return format!("{bytes}B");
+            }
+            //小于10保留一位小数，否则取整，例如 5.9Gi、483Mi
+            let suffix = if si { "" } else { "i" };
+            let mut value = bytes as f64;
+            for unit in ["K", "M", "G", "T", "P", "E"] {
+                value /= base as f64;
+                if value < base as f64 || unit == "E" {
+                    return if value < 10. {
+                        format!("{value:.1}{unit}{suffix}")
+                    } else {
+                        format!("{}{unit}{suffix}", value as u64)
+                    };
+                }
+            }
+            unreachable!()
+        }
+    }
+}
+
+/// 按 procps free 的格式输出: 标签宽8，数值列宽12并右对齐
+pub fn format_free(stats: &FreeStats, options: &FreeOptions) -> String {
+    let FreeOptions { unit, si, wide, total, lohi, committed } = *options;
+    let size = |bytes: u64| format!(" {:>11}", free_size(bytes, unit, si));
+    let mut out = String::new();
+    let mut line = |label: &str, values: &[u64]| {
+        out.push_str(&format!("{label:8}"));
+        for bytes in values {
+            out.push_str(&size(*bytes));
+        }
+        out.push('\n');
+    };
+
+    let titles: &[&str] = if wide {
+        &["total", "used", "free", "shared", "buffers", "cache", "available"]
+    } else {
+        &["total", "used", "free", "shared", "buff/cache", "available"]
+    };
+    let mut header = format!("{:8}", "");
+    for title in titles {
+        header.push_str(&format!(" {title:>11}"));
+    }
+    header.push('\n');
+
+    let mem = if wide {
+        vec![stats.mem_total, stats.mem_used, stats.mem_free, stats.shared, stats.buffers, stats.cache, stats.available]
+    } else {
+        vec![stats.mem_total, stats.mem_used, stats.mem_free, stats.shared, stats.buffers + stats.cache, stats.available]
+    };
+    line("Mem:", &mem);
+    if lohi {
+        //与 procps 相同，低端内存的 used 为 total - free
+        line("Low:", &[stats.low_total, stats.low_total.saturating_sub(stats.low_free), stats.low_free]);
+        line("High:", &[stats.high_total, stats.high_total.saturating_sub(stats.high_free), stats.high_free]);
+    }
+    line("Swap:", &[stats.swap_total, stats.swap_used, stats.swap_free]);
+    if total {
+        line("Total:", &[stats.mem_total + stats.swap_total, stats.mem_used + stats.swap_used, stats.mem_free + stats.swap_free]);
+    }
+    if committed {
+        line("Comm:", &[stats.commit_limit, stats.committed, stats.commit_limit.saturating_sub(stats.committed)]);
+    }
+    header + &out
+}
+
+#[test]
+fn test_parse_meminfo() {
+    let text = "MemTotal:        6153852 kB
+MemFree:         4390124 kB
+Committed_AS:     912340 kB
+HugePages_Total:       0
+bad line
+";
+    let meminfo = parse_meminfo(text);
+    assert_eq!(meminfo.len(), 4);
+    assert_eq!(meminfo["MemTotal"], 6153852 * 1024);
+    assert_eq!(meminfo["Committed_AS"], 912340 * 1024);
+    assert_eq!(meminfo["HugePages_Total"], 0);
+}
+
+#[cfg(test)]
+const MEMINFO: &str = "MemTotal:        6158152 kB
+MemFree:         4482020 kB
+MemAvailable:    5662612 kB
+Buffers:           61792 kB
+Cached:          1319756 kB
+SwapTotal:             0 kB
+SwapFree:              0 kB
+Shmem:              9484 kB
+SReclaimable:      34464 kB
+CommitLimit:     3079076 kB
+Committed_AS:     338696 kB
+";
+
+#[test]
+fn test_format_free() {
+    let stats = FreeStats::from_meminfo(&parse_meminfo(MEMINFO));
+    assert_eq!(stats.mem_used, 495540 * 1024);
+    assert_eq!(stats.cache, 1354220 * 1024);
+
+    //与 procps-ng 4.0.2 的输出一致
+    assert_eq!(format_free(&stats, &FreeOptions::default()), "               total        used        free      shared  buff/cache   available
+Mem:         6158152      495540     4482020        9484     1416012     5662612
+Swap:              0           0           0
+");
+    assert_eq!(format_free(&stats, &FreeOptions { wide: true, total: true, ..FreeOptions::default() }), "               total        used        free      shared     buffers       cache   available
+Mem:         6158152      495540     4482020        9484       61792     1354220     5662612
+Swap:              0           0           0
+Total:       6158152      495540     4482020
+");
+    assert_eq!(format_free(&stats, &FreeOptions { unit: FreeUnit::Human, ..FreeOptions::default() }), "               total        used        free      shared  buff/cache   available
+Mem:           5.9Gi       483Mi       4.3Gi       9.3Mi       1.4Gi       5.4Gi
+Swap:             0B          0B          0B
+");
+    //free -l -v
+    assert_eq!(format_free(&stats, &FreeOptions { lohi: true, committed: true, ..FreeOptions::default() }), "               total        used        free      shared  buff/cache   available
+Mem:         6158152      495540     4482020        9484     1416012     5662612
+Low:         6158152     1676132     4482020
+High:              0           0           0
+Swap:              0           0           0
+Comm:        3079076      338696     2740380
+");
+    //--kilo 即 -k --si
+    assert_eq!(format_free(&stats, &FreeOptions { si: true, ..FreeOptions::default() }).lines().nth(1).unwrap(),
+               "Mem:         6305947      507432     4589588        9711     1449996     5798514");
+}
+
+#[test]
+fn test_free_size() {
+    assert_eq!(free_size(1536, FreeUnit::Bytes, false), "1536");
+    assert_eq!(free_size(1536, FreeUnit::Kibi, false), "1");
+    assert_eq!(free_size(3 << 30, FreeUnit::Mebi, false), "3072");
+    assert_eq!(free_size(3 << 30, FreeUnit::Gibi, false), "3");
+    assert_eq!(free_size(5 << 40, FreeUnit::Tebi, false), "5");
+    assert_eq!(free_size(5 << 50, FreeUnit::Pebi, false), "5");
+    assert_eq!(free_size(1500, FreeUnit::Human, true), "1.5K");
+    assert_eq!(free_size(999, FreeUnit::Human, true), "999B");
+}
+
+#[test]
+fn test_parse_swaps() {
+    let text = "Filename\t\t\t\tType\t\tSize\t\tUsed\t\tPriority
+/dev/sda2                               partition\t8388604\t\t1024\t\t-2
+/swap\\040file                          file\t\t2097148\t\t0\t\t10
+";
+    let devices = parse_swaps(text);
+    assert_eq!(devices.len(), 2);
+    assert_eq!(devices[0], SwapDevice {
+        name: "/dev/sda2".to_string(),
+        kind: "partition".to_string(),
+        size: 8388604 * 1024,
+        used: 1024 * 1024,
+        priority: -2,
+    });
+    assert_eq!(devices[1].name, "/swap file");
+    assert_eq!(devices[1].priority, 10);
+    assert!(parse_swaps("Filename\tType\tSize\tUsed\tPriority\n").is_empty());
+}
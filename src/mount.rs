@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+use std::os::unix::io::AsRawFd;
+
+extern crate libc;
+
+pub const PROC_SELF_MOUNTINFO: &str = "/proc/self/mountinfo";
+
+/// /proc/self/mountinfo 中的一行
+/// 36 35 98:0 /mnt1 /mnt/parent rw,noatime master:1 - ext3 /dev/root rw,errors=continue
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MountInfo {
+    pub mount_id: u32,
+    pub parent_id: u32,
+    pub major_minor: String,
+    pub root: String,
+    pub mount_point: String,
+    pub mount_options: String,
+    pub fs_type: String,
+    pub source: String,
+    pub super_options: String,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MountEventKind {
+    Added,
+    Removed,
+    Remounted,
+}
+
+impl MountEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MountEventKind::Added => "added",
+            MountEventKind::Removed => "removed",
+            MountEventKind::Remounted => "remounted",
+        }
+    }
+}
+
+/// 挂载表变化事件，remounted 事件的 old_options、old_super_options 为变化前的挂载选项和超级块选项
+#[derive(Debug, Clone, PartialEq)]
+pub struct MountEvent {
+    pub kind: MountEventKind,
+    pub mount: MountInfo,
+    pub old_options: Option<String>,
+    pub old_super_options: Option<String>,
+}
+
+/// 解析 /proc/self/mountinfo，格式错误的行忽略
+pub fn parse_mountinfo(text: &str) -> Vec<MountInfo> {
+    let mut mounts = Vec::new();
+    for line in text.lines() {
+        let Some((left, right)) = line.split_once(" - ") else { continue };
+        let left: Vec<&str> = left.split_whitespace().collect();
+        let right: Vec<&str> = right.split_whitespace().collect();
+        if left.len() < 6 || right.len() < 2 {
+            continue;
+        }
+        let (Ok(mount_id), Ok(parent_id)) = (left[0].parse(), left[1].parse()) else { continue };
+        mounts.push(MountInfo {
+            mount_id,
+            parent_id,
+            major_minor: left[2].to_string(),
+            root: unescape_octal(left[3]),
+            mount_point: unescape_octal(left[4]),
+            mount_options: left[5].to_string(),
+            fs_type: right[0].to_string(),
+            source: unescape_octal(right[1]),
+            super_options: right.get(2).map(|s| s.to_string()).unwrap_or_default(),
+        });
+    }
+    mounts
+}
+
+/// 内核使用八进制转义路径中的空格、制表符、换行和反斜杠，例如 \040
+fn unescape_octal(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 4 <= bytes.len() {
+            let octal = std::str::from_utf8(&bytes[i + 1..i + 4]).ok();
+            if let Some(byte) = octal.and_then(|octal| u8::from_str_radix(octal, 8).ok()) {
+                out.push(byte);
+                i += 4;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// path 是否为挂载点，path 需为规范化后的绝对路径
+pub fn is_mount_point(mounts: &[MountInfo], path: &str) -> bool {
+    mounts.iter().any(|mount| mount.mount_point == path)
+}
+
+/// 比较前后两次的挂载表: 按 mount id 匹配，挂载选项变化视为 remounted
+pub fn diff_mounts(old: &[MountInfo], new: &[MountInfo]) -> Vec<MountEvent> {
+    let old_map: HashMap<u32, &MountInfo> = old.iter().map(|m| (m.mount_id, m)).collect();
+    let new_map: HashMap<u32, &MountInfo> = new.iter().map(|m| (m.mount_id, m)).collect();
+
+    let mut events = Vec::new();
+    for mount in old {
+        if !new_map.contains_key(&mount.mount_id) {
+            events.push(MountEvent { kind: MountEventKind::Removed, mount: mount.clone(), old_options: None, old_super_options: None });
+        }
+    }
+    for mount in new {
+        match old_map.get(&mount.mount_id) {
+            None => events.push(MountEvent { kind: MountEventKind::Added, mount: mount.clone(), old_options: None, old_super_options: None }),
+            Some(old) if old.mount_options != mount.mount_options || old.super_options != mount.super_options => {
+                events.push(MountEvent {
+                    kind: MountEventKind::Remounted,
+                    mount: mount.clone(),
+                    old_options: Some(old.mount_options.clone()),
+                    old_super_options: Some(old.super_options.clone()),
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    events
+}
+
+/// 从头重新读取挂载表，读取后内核才会重置 poll 的变化通知
+pub fn read_mounts(file: &mut File) -> io::Result<Vec<MountInfo>> {
+    let mut text = String::new();
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_string(&mut text)?;
+    Ok(parse_mountinfo(&text))
+}
+
+/// 阻塞等待挂载表变化: mountinfo 在挂载、卸载、重新挂载时产生 POLLPRI/POLLERR 事件
+pub fn wait_change(file: &File) -> io::Result<()> {
+    let mut fds = libc::pollfd {
+        fd: file.as_raw_fd(),
+        events: libc::POLLPRI | libc::POLLERR,
+        revents: 0,
+    };
+    loop {
+        let result = unsafe { libc::poll(&mut fds, 1, -1) };
+        if result >= 0 {
+            return Ok(());
+        }
+        let err = io::Error::last_os_error();
+        if err.kind() != io::ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+}
+
+#[cfg(test)]
+const MOUNTINFO: &str = r"22 1 252:1 / / rw,relatime shared:1 - ext4 /dev/vda1 rw,errors=remount-ro
+23 22 0:21 / /proc rw,nosuid,nodev,noexec,relatime shared:12 - proc proc rw
+40 22 8:17 / /media/usb\040stick rw,nosuid,nodev,relatime shared:30 - vfat /dev/sdb1 rw,fmask=0022
+bad line
+";
+
+#[test]
+fn test_parse_mountinfo() {
+    let mounts = parse_mountinfo(MOUNTINFO);
+    assert_eq!(mounts.len(), 3);
+    assert_eq!(mounts[0].mount_id, 22);
+    assert_eq!(mounts[0].parent_id, 1);
+    assert_eq!(mounts[0].major_minor, "252:1");
+    assert_eq!(mounts[0].fs_type, "ext4");
+    assert_eq!(mounts[0].source, "/dev/vda1");
+    assert_eq!(mounts[0].super_options, "rw,errors=remount-ro");
+    assert_eq!(mounts[2].mount_point, "/media/usb stick");
+    assert_eq!(mounts[2].mount_options, "rw,nosuid,nodev,relatime");
+}
+
+#[test]
+fn test_is_mount_point() {
+    let mounts = parse_mountinfo(MOUNTINFO);
+    assert!(is_mount_point(&mounts, "/"));
+    assert!(is_mount_point(&mounts, "/media/usb stick"));
+    assert!(!is_mount_point(&mounts, "/media"));
+    assert!(!is_mount_point(&mounts, "/proc/1"));
+}
+
+#[test]
+fn test_diff_mounts() {
+    let old = parse_mountinfo(MOUNTINFO);
+    let mut new = old.clone();
+    new.remove(2);
+    new[0].mount_options = "ro,relatime".to_string();
+    new[1].super_options = "ro".to_string();
+    new.push(MountInfo {
+        mount_id: 41,
+        parent_id: 22,
+        mount_point: "/mnt/nfs".to_string(),
+        fs_type: "nfs4".to_string(),
+        ..MountInfo::default()
+    });
+
+    let events = diff_mounts(&old, &new);
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[0].kind, MountEventKind::Removed);
+    assert_eq!(events[0].mount.mount_point, "/media/usb stick");
+    assert_eq!(events[1].kind, MountEventKind::Remounted);
+    assert_eq!(events[1].old_options.as_deref(), Some("rw,relatime"));
+    //只有超级块选项变化时，old_super_options 与新值不同
+    assert_eq!(events[2].kind, MountEventKind::Remounted);
+    assert_eq!(events[2].old_options.as_deref(), Some(events[2].mount.mount_options.as_str()));
+    assert_eq!(events[2].old_super_options.as_deref(), Some("rw"));
+    assert_eq!(events[3].kind, MountEventKind::Added);
+    assert_eq!(events[3].mount.mount_point, "/mnt/nfs");
+    assert!(diff_mounts(&old, &old).is_empty());
+}
@@ -0,0 +1,75 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub const PROC_PRESSURE: &str = "/proc/pressure";
+
+/// PSI 资源，对应 /proc/pressure 下的文件名
+pub const PRESSURE_RESOURCES: [&str; 3] = ["cpu", "memory", "io"];
+
+/// /proc/pressure/* 中的一行，avg 为最近 10/60/300 秒内停顿时间的百分比，total 为累计停顿微秒数
+/// some avg10=5.56 avg60=3.33 avg300=2.28 total=28294109
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Pressure {
+    /// some: 至少一个任务停顿; full: 全部非空闲任务同时停顿
+    pub kind: String,
+    pub avg10: f64,
+    pub avg60: f64,
+    pub avg300: f64,
+    pub total: u64,
+}
+
+/// 解析 PSI 文件，格式错误的行忽略
+pub fn parse_pressure(text: &str) -> Vec<Pressure> {
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        let mut fields = line.split_whitespace();
+        let Some(kind) = fields.next() else { continue };
+        let mut pressure = Pressure { kind: kind.to_string(), ..Pressure::default() };
+        for field in fields {
+            let Some((key, value)) = field.split_once('=') else { continue };
+            match key {
+                "avg10" => pressure.avg10 = value.parse().unwrap_or_default(),
+                "avg60" => pressure.avg60 = value.parse().unwrap_or_default(),
+                "avg300" => pressure.avg300 = value.parse().unwrap_or_default(),
+                "total" => pressure.total = value.parse().unwrap_or_default(),
+                _ => {}
+            }
+        }
+        lines.push(pressure);
+    }
+    lines
+}
+
+/// 读取某个资源的 PSI，内核没有启用 PSI(CONFIG_PSI 或 psi=0)时返回错误
+pub fn read_pressure(proc_pressure: &Path, resource: &str) -> io::Result<Vec<Pressure>> {
+    Ok(parse_pressure(&fs::read_to_string(proc_pressure.join(resource))?))
+}
+
+/// 内核不支持 PSI: CONFIG_PSI 未启用时文件不存在，psi=0 启动时文件存在但读取返回 EOPNOTSUPP
+pub fn is_unavailable(err: &io::Error) -> bool {
+    matches!(err.kind(), io::ErrorKind::NotFound | io::ErrorKind::Unsupported) || err.raw_os_error() == Some(libc::EOPNOTSUPP)
+}
+
+#[test]
+fn test_parse_pressure() {
+    let text = "some avg10=5.56 avg60=3.33 avg300=2.28 total=28294109
+full avg10=0.00 avg60=0.00 avg300=0.00 total=0
+";
+    let lines = parse_pressure(text);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0], Pressure { kind: "some".to_string(), avg10: 5.56, avg60: 3.33, avg300: 2.28, total: 28294109 });
+    assert_eq!(lines[1].kind, "full");
+    assert_eq!(lines[1].total, 0);
+    assert!(parse_pressure("").is_empty());
+}
+
+#[test]
+fn test_read_pressure() {
+    let dir = crate::common::test_dir("pressure");
+    fs::write(dir.join("cpu"), "some avg10=1.00 avg60=0.50 avg300=0.10 total=1000\n").unwrap();
+    assert_eq!(read_pressure(&dir, "cpu").unwrap()[0].avg10, 1.);
+    assert!(is_unavailable(&read_pressure(&dir, "io").unwrap_err()));
+    assert!(is_unavailable(&io::Error::from_raw_os_error(libc::EOPNOTSUPP)));
+    assert!(!is_unavailable(&io::Error::from_raw_os_error(libc::EACCES)));
+}
@@ -0,0 +1,248 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+
+pub const PROC: &str = "/proc";
+
+/// 进程使用的文件: 打开的文件描述符、工作目录、根目录或内存映射
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenFile {
+    pub pid: u32,
+    pub command: String,
+    /// fd N / cwd / root / mmap
+    pub access: String,
+    pub path: String,
+}
+
+/// 全部进程号，按从小到大排序
+pub fn pids(proc_root: &Path) -> Vec<u32> {
+    let mut pids: Vec<u32> = match fs::read_dir(proc_root) {
+        Ok(entries) => entries.flatten().filter_map(|entry| entry.file_name().to_str()?.parse().ok()).collect(),
+        Err(_) => Vec::new(),
+    };
+    pids.sort();
+    pids
+}
+
+/// 进程名(/proc/PID/comm)
+pub fn command(proc_root: &Path, pid: u32) -> String {
+    fs::read_to_string(proc_root.join(pid.to_string()).join("comm"))
+        .map(|comm| comm.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// 解析 /proc/PID/maps 的一行，返回 (设备号, inode, 路径)，匿名映射返回 None
+/// 7f2c4a600000-7f2c4a628000 r--p 00000000 fd:01 1835271 /usr/lib/x86_64-linux-gnu/libc.so.6
+pub fn parse_maps_line(line: &str) -> Option<(u64, u64, String)> {
+    let mut fields = line.splitn(6, char::is_whitespace);
+    let _range = fields.next()?;
+    let _perms = fields.next()?;
+    let _offset = fields.next()?;
+    let (major, minor) = fields.next()?.split_once(':')?;
+    let inode: u64 = fields.next()?.parse().ok()?;
+    let path = fields.next().unwrap_or_default().trim().to_string();
+    if inode == 0 || path.is_empty() {
+        return None;
+    }
+    let major = u64::from_str_radix(major, 16).ok()?;
+    let minor = u64::from_str_radix(minor, 16).ok()?;
+    Some((makedev(major, minor), inode, path))
+}
+
+/// 按 Linux(glibc) 的编码规则把主、次设备号合成 st_dev
+fn makedev(major: u64, minor: u64) -> u64 {
+    ((major & 0xfffff000) << 32) | ((major & 0xfff) << 8) | ((minor & 0xffffff00) << 12) | (minor & 0xff)
+}
+
+/// 查找使用了指定文件系统(设备号 st_dev)的进程，没有权限读取的进程忽略
+pub fn busy_files(proc_root: &Path, dev: u64) -> Vec<OpenFile> {
+    let mut files = Vec::new();
+    for pid in pids(proc_root) {
+        let dir = proc_root.join(pid.to_string());
+        let mut found: Vec<(String, String)> = Vec::new();
+
+        for name in ["cwd", "root"] {
+            let link = dir.join(name);
+            if fs::metadata(&link).map(|m| m.dev() == dev).unwrap_or(false) {
+                let path = fs::read_link(&link).map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+                found.push((name.to_string(), path));
+            }
+        }
+
+        if let Ok(entries) = fs::read_dir(dir.join("fd")) {
+            let mut fds: Vec<(u32, std::path::PathBuf)> = entries.flatten()
+                .filter_map(|entry| Some((entry.file_name().to_str()?.parse().ok()?, entry.path())))
+                .collect();
+            fds.sort();
+            for (fd, link) in fds {
+                if fs::metadata(&link).map(|m| m.dev() == dev).unwrap_or(false) {
+                    let path = fs::read_link(&link).map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+                    found.push((format!("fd {fd}"), path));
+                }
+            }
+        }
+
+        if let Ok(maps) = fs::read_to_string(dir.join("maps")) {
+            let mut paths: Vec<String> = Vec::new();
+            for (map_dev, _, path) in maps.lines().filter_map(parse_maps_line) {
+                if map_dev == dev && !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+            found.extend(paths.into_iter().map(|path| ("mmap".to_string(), path)));
+        }
+
+        if found.is_empty() {
+            continue;
+        }
+        let command = command(proc_root, pid);
+        for (access, path) in found {
+            files.push(OpenFile { pid, command: command.clone(), access, path });
+        }
+    }
+    files
+}
+
+/// 读取 /proc/PID/status 中以 kB 为单位的字段(VmSwap、VmRSS 等)，返回字节数
+pub fn parse_status_kb(status: &str, key: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.split(':').next() == Some(key))?;
+    let value: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(value * 1024)
+}
+
+/// 使用了交换分区的进程: (进程号, 进程名, 交换字节数)，按使用量从大到小排序
+pub fn swap_usage(proc_root: &Path) -> Vec<(u32, String, u64)> {
+    let mut usage = Vec::new();
+    for pid in pids(proc_root) {
+        let Ok(status) = fs::read_to_string(proc_root.join(pid.to_string()).join("status")) else { continue };
+        match parse_status_kb(&status, "VmSwap") {
+            Some(swap) if swap > 0 => usage.push((pid, command(proc_root, pid), swap)),
+            _ => {}
+        }
+    }
+    usage.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)));
+    usage
+}
+
+/// 已删除(unlink)但仍被进程打开的文件，在关闭前继续占用磁盘空间
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeletedFile {
+    pub pid: u32,
+    pub command: String,
+    pub fd: u32,
+    /// 删除前的路径，不含 " (deleted)" 后缀
+    pub path: String,
+    pub dev: u64,
+    pub inode: u64,
+    /// 实际占用的磁盘空间，即关闭后释放的空间
+    pub size: u64,
+    /// 文件长度，稀疏文件或预分配(fallocate)的文件与 size 不同
+    pub apparent_size: u64,
+}
+
+/// 扫描 /proc/*/fd 中链接计数为0的普通文件，按进程号和 fd 排序
+pub fn deleted_files(proc_root: &Path) -> Vec<DeletedFile> {
+    let mut files = Vec::new();
+    for pid in pids(proc_root) {
+        let Ok(entries) = fs::read_dir(proc_root.join(pid.to_string()).join("fd")) else { continue };
+        let mut fds: Vec<(u32, std::path::PathBuf)> = entries.flatten()
+            .filter_map(|entry| Some((entry.file_name().to_str()?.parse().ok()?, entry.path())))
+            .collect();
+        fds.sort();
+
+        let mut command_name: Option<String> = None;
+        for (fd, link) in fds {
+            let Ok(meta) = fs::metadata(&link) else { continue };
+            if !meta.is_file() || meta.nlink() > 0 {
+                continue;
+            }
+            let target = fs::read_link(&link).map(|p| p.to_string_lossy().to_string()).unwrap_or_default();
+            let path = target.strip_suffix(" (deleted)").unwrap_or(&target).to_string();
+            let command = command_name.get_or_insert_with(|| command(proc_root, pid)).clone();
+            files.push(DeletedFile {
+                pid,
+                command,
+                fd,
+                path,
+                dev: meta.dev(),
+                inode: meta.ino(),
+                //st_blocks 的单位固定为512字节
+                size: meta.blocks() * 512,
+                apparent_size: meta.size(),
+            });
+        }
+    }
+    files
+}
+
+/// 按设备号统计已删除文件占用的空间，同一个文件被多个进程或 fd 打开时只计算一次
+pub fn deleted_space(files: &[DeletedFile]) -> HashMap<u64, u64> {
+    let mut seen = HashSet::new();
+    let mut space = HashMap::new();
+    for file in files {
+        if seen.insert((file.dev, file.inode)) {
+            *space.entry(file.dev).or_insert(0) += file.size;
+        }
+    }
+    space
+}
+
+#[test]
+fn test_parse_maps_line() {
+    let line = "7f2c4a600000-7f2c4a628000 r--p 00000000 fd:01 1835271                    /usr/lib/x86_64-linux-gnu/libc.so.6";
+    let (dev, inode, path) = parse_maps_line(line).unwrap();
+    assert_eq!(dev, 0xfd01);
+    assert_eq!(inode, 1835271);
+    assert_eq!(path, "/usr/lib/x86_64-linux-gnu/libc.so.6");
+
+    let line = "7f2c4a800000-7f2c4a900000 rw-p 00000000 00:00 0 ";
+    assert_eq!(parse_maps_line(line), None);
+    let line = "7ffd1c3c2000-7ffd1c3e3000 rw-p 00000000 00:00 0                          [stack]";
+    assert_eq!(parse_maps_line(line), None);
+}
+
+#[test]
+fn test_parse_status_kb() {
+    let status = "Name:\tpostgres\nVmRSS:\t    4096 kB\nVmSwap:\t    2048 kB\n";
+    assert_eq!(parse_status_kb(status, "VmRSS"), Some(4096 * 1024));
+    assert_eq!(parse_status_kb(status, "VmSwap"), Some(2048 * 1024));
+    //内核线程没有 VmSwap
+    assert_eq!(parse_status_kb("Name:\tkthreadd\n", "VmSwap"), None);
+}
+
+#[test]
+fn test_open_files() {
+    use std::io::Write;
+
+    let dir = crate::common::test_dir("open-files");
+    let held = dir.join("held.log");
+    let _held = fs::File::create(&held).unwrap();
+    let path = dir.join("rotated.log");
+    let mut file = fs::File::create(&path).unwrap();
+    file.write_all(&[1; 4096]).unwrap();
+    //稀疏文件: 长度1M，只有开头4K占用磁盘
+    file.set_len(1 << 20).unwrap();
+    file.sync_all().unwrap();
+    let allocated = file.metadata().unwrap().blocks() * 512;
+    let _again = fs::File::open(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    let dev = fs::metadata(&*dir).unwrap().dev();
+    let pid = std::process::id();
+
+    let files = busy_files(Path::new(PROC), dev);
+    assert!(files.iter().any(|f| f.pid == pid && f.access.starts_with("fd ") && f.path == held.to_string_lossy()));
+
+    let files = deleted_files(Path::new(PROC));
+    let own: Vec<&DeletedFile> = files.iter().filter(|f| f.pid == pid && f.path == path.to_string_lossy()).collect();
+    assert_eq!(own.len(), 2);
+    assert!(own.iter().all(|f| f.dev == dev && f.size == allocated && f.apparent_size == 1 << 20));
+    assert!(allocated < 1 << 20);
+
+    let own: Vec<DeletedFile> = own.into_iter().cloned().collect();
+    assert_eq!(deleted_space(&own).get(&dev), Some(&allocated));
+
+    //按交换分区使用量从大到小排序
+    let usage = swap_usage(Path::new(PROC));
+    assert!(usage.windows(2).all(|w| w[0].2 >= w[1].2));
+}
@@ -1,15 +1,30 @@
 use colored::{Color, Colorize, Style, Styles};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::fs;
+use std::fs::File;
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::time::Duration;
 use sysinfo::{CpuRefreshKind, Disks, MemoryRefreshKind, RefreshKind, System};
 
-use crate::disk::disk_info;
-use crate::common::{BaseSize, BlockSize, PrettySize};
-use crate::table::{Column, CombineString, RenderArgs, Table};
-use crate::Commands;
+use crate::memory::{format_free, read_meminfo, read_swaps, FreeOptions, FreeStats, FreeUnit, MEMINFO_DETAILS, PROC_MEMINFO, PROC_SWAPS};
+use crate::cgroup::{read_cgroup_limits, PROC_SELF_CGROUP, SYS_FS_CGROUP};
+use crate::cpu::{cpu_number, parse_cpu_flags, parse_cpu_list, read_cpufreq, read_stat, read_tasks, read_topology, read_vulnerabilities, CpuFreq, CPU_STATES, FEATURE_GROUPS, PROC_CPUINFO, PROC_LOADAVG, PROC_STAT, SYS_CPU, SYS_NODE, SYS_VULNERABILITIES};
+use crate::pressure::{is_unavailable, read_pressure, PRESSURE_RESOURCES, PROC_PRESSURE};
+use crate::irq::{diff_interrupts, irq_affinity, parse_stat_counters, read_interrupts, IrqCounts, PROC_INTERRUPTS, PROC_IRQ, PROC_SOFTIRQS};
+use crate::disk::{device_label, device_link, device_links, disk_info, fs_class, kernel_name, physical_device, FsClass, StatvfsError, DEV_DISK, SYS_BLOCK, SYS_CLASS_BLOCK};
+use crate::md::{degraded_array, read_md_arrays, PROC_MDSTAT};
+use crate::process::{busy_files, deleted_files, deleted_space, swap_usage, DeletedFile, PROC};
+use crate::mount::{diff_mounts, is_mount_point, parse_mountinfo, read_mounts, wait_change, MountEvent, MountEventKind, PROC_SELF_MOUNTINFO};
+use crate::common::{format_local_time, format_rfc3339, format_uptime, json_string, now_secs, parse_duration, BaseSize, BlockSize, BlockSizeParseError, PrettySize};
+use crate::table::{aggregate_percent, aggregate_row, aggregate_values, Aggregate, AggregateArgs, BarStyle, BarStyleParseError, Column, CombineString, RenderArgs, Table, Thresholds};
+use crate::{Commands, CpuArgs, MemoryArgs};
 
 #[derive(Debug)]
 pub struct SysInfo {
     system: System,
+    thresholds: Thresholds,
 }
 
 #[derive(Debug)]
@@ -30,9 +45,16 @@ impl SysInfo {
     fn new_with_specifics(refreshes: RefreshKind) -> Self {
         Self {
             system: System::new_with_specifics(refreshes),
+            thresholds: Thresholds::default(),
         }
     }
 
+    /// 设置 Use% 列的颜色阈值
+    pub fn with_thresholds(mut self, thresholds: Thresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
     pub fn new() -> Self {
         Self::new_with_specifics(RefreshKind::new())
     }
@@ -55,11 +77,15 @@ impl SysInfo {
         println!();
         println!();
 
-        self.print_cpu(true);
+        let args = CpuArgs {
+            details: true,
+            ..CpuArgs::default()
+        };
+        self.print_cpu(args);
         println!();
         println!();
 
-        self.print_memory();
+        self.print_memory(MemoryArgs::default());
         println!();
         println!();
 
@@ -71,6 +97,12 @@ impl SysInfo {
             human_readable: false,
             si: false,
             block_size: "".to_string(),
+            bar: "".to_string(),
+            group_by: "".to_string(),
+            watch_mounts: false,
+            json: false,
+            busy: "".to_string(),
+            deleted: false,
         };
         self.print_disk(cmd);
 
@@ -88,16 +120,44 @@ impl SysInfo {
         let os_version = System::os_version().unwrap_or_default();
         let kernel_version = System::kernel_version().unwrap_or_default();
         let host_name = System::host_name().unwrap_or_default();
+        let load = System::load_average();
+        let load_average = format!("{:.2}, {:.2}, {:.2}", load.one, load.five, load.fifteen);
+        let uptime = format_uptime(System::uptime());
+        let boot_time = format_local_time(System::boot_time() as i64);
 
         let width = 15;
         println!("{:width$} {}", "OS Name:".color(Color::Red), os_name.color(Color::Green));
         println!("{:width$} {}", "OS Version:".color(Color::Red), os_version.color(Color::Blue));
         println!("{:width$} {}", "Kernel Version:".color(Color::Red), kernel_version.color(Color::Yellow));
         println!("{:width$} {}", "Hostname:".color(Color::Red), host_name.color(Color::Magenta));
+        println!("{:width$} {}", "Load Average:".color(Color::Red), load_average.color(Color::Cyan));
+        println!("{:width$} {}", "Uptime:".color(Color::Red), uptime.color(Color::BrightGreen));
+        println!("{:width$} {}", "Boot Time:".color(Color::Red), boot_time.color(Color::BrightBlue));
+        //任务数只有 Linux 的 /proc/loadavg 提供
+        if let Some(tasks) = read_tasks(Path::new(PROC_LOADAVG)) {
+            let tasks = format!("{} running, {} total", tasks.running, tasks.total);
+            println!("{:width$} {}", "Tasks:".color(Color::Red), tasks.color(Color::BrightYellow));
+        }
     }
 
     /// 打印CPU信息
-    pub fn print_cpu(&mut self, details: bool) {
+    pub fn print_cpu(&mut self, args: CpuArgs) {
+        let CpuArgs { details, bar, total, interval, samples, breakdown, topology, features } = args;
+
+        let bar = match parse_bar(&bar) {
+            Ok(val) => val,
+            Err(err) => {
+                eprintln!("{err}: {bar}");
+                return;
+            }
+        };
+
+        //容器内的 CPU 配额和 cpuset，只在比主机少时显示
+        let cgroup = read_cgroup_limits(Path::new(PROC_SELF_CGROUP), Path::new(SYS_FS_CGROUP)).unwrap_or_default();
+        let host_cpus = self.system.cpus().len();
+        let cpu_quota = cgroup.cpu_quota.filter(|quota| *quota < host_cpus as f64);
+        let cpuset = cgroup.cpuset.filter(|cpuset| parse_cpu_list(cpuset).len() < host_cpus);
+
         let columns = vec![
             Column {
                 title: "".to_string(),
@@ -113,6 +173,22 @@ impl SysInfo {
                 color: Some(Color::Green),
                 ..Column::default()
             },
+            Column {
+                title: "Min%".to_string(),
+                key: "cpu_min".to_string(),
+                hidden: samples < 2,
+                right_align: true,
+                color: Some(Color::Cyan),
+                ..Column::default()
+            },
+            Column {
+                title: "Max%".to_string(),
+                key: "cpu_max".to_string(),
+                hidden: samples < 2,
+                right_align: true,
+                color: Some(Color::Magenta),
+                ..Column::default()
+            },
             Column {
                 title: "Core".to_string(),
                 key: "cpu_core".to_string(),
@@ -127,27 +203,85 @@ impl SysInfo {
                 color: Some(Color::Blue),
                 ..Column::default()
             },
+            Column {
+                title: "Quota".to_string(),
+                key: "cpu_quota".to_string(),
+                hidden: cpu_quota.is_none(),
+                right_align: true,
+                color: Some(Color::BrightRed),
+                ..Column::default()
+            },
+            Column {
+                title: "Cpuset".to_string(),
+                key: "cpuset".to_string(),
+                hidden: cpuset.is_none(),
+                color: Some(Color::BrightMagenta),
+                ..Column::default()
+            },
         ];
 
-        // Sleeping to let time for the system to run for long
-        // enough to have useful information.
-        std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
-        self.system.refresh_cpu(); // Refreshing CPU information.
+        //采样间隔不能小于 sysinfo 的最小刷新间隔，否则读到的使用率没有意义
+        let interval = if interval.is_empty() {
+            sysinfo::MINIMUM_CPU_UPDATE_INTERVAL
+        } else {
+            match parse_duration(&interval) {
+                Ok(val) => val.max(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL),
+                Err(err) => {
+                    eprintln!("{err}: {interval}");
+                    return;
+                }
+            }
+        };
+        let samples = samples.max(1);
+
+        //--breakdown、--topology、--features 互斥，也不能与 --samples、--details 等一起使用(由 clap 检查)
+        if breakdown {
+            self.print_cpu_breakdown(interval);
+            return;
+        }
+        if topology {
+            self.print_cpu_topology();
+            return;
+        }
+        if features {
+            self.print_cpu_features();
+            return;
+        }
+
+        //多次采样，记录全局和每个逻辑核心的使用率
+        let mut global_samples: Vec<f32> = Vec::new();
+        let mut cpu_samples: Vec<Vec<f32>> = Vec::new();
+        for _ in 0..samples {
+            // Sleeping to let time for the system to run for long
+            // enough to have useful information.
+            std::thread::sleep(interval);
+            self.system.refresh_cpu(); // Refreshing CPU information.
+
+            global_samples.push(self.system.global_cpu_info().cpu_usage());
+            let cpus = self.system.cpus();
+            cpu_samples.resize(cpus.len(), Vec::new());
+            for (index, cpu) in cpus.iter().enumerate() {
+                cpu_samples[index].push(cpu.cpu_usage());
+            }
+        }
 
         //全局 global
-        let info = self.system.global_cpu_info();
         let core = self.system.physical_core_count();
         let cpus = self.system.cpus();
-        let cpu_usage = format!("{:.2}%", info.cpu_usage());
+        let (avg, min, max) = usage_stats(&global_samples);
         let cpu_core = format!("{}", core.unwrap_or_default());
         let cpu_thread = format!("{}", cpus.len());
 
         let mut data = Vec::new();
         data.push(HashMap::from([
             ("title".to_string(), "CPU:".to_string()),
-            ("cpu_usage".to_string(), cpu_usage),
+            ("cpu_usage".to_string(), format!("{avg:.2}%")),
+            ("cpu_min".to_string(), format!("{min:.2}%")),
+            ("cpu_max".to_string(), format!("{max:.2}%")),
             ("cpu_core".to_string(), cpu_core),
             ("cpu_thread".to_string(), cpu_thread),
+            ("cpu_quota".to_string(), cpu_quota.map(|quota| format!("{quota:.2}")).unwrap_or_default()),
+            ("cpuset".to_string(), cpuset.unwrap_or_default()),
         ]));
 
         let table = Table::new(columns, data, HashMap::new());
@@ -155,6 +289,28 @@ impl SysInfo {
 
         if details {
             //明细 details
+            //按 cpuN 中的编号读取，离线或不连续的 CPU 不会错位
+            let freqs: Vec<CpuFreq> = cpus.iter()
+                .map(|cpu| cpu_number(cpu.name()).map(|n| read_cpufreq(Path::new(SYS_CPU), n)).unwrap_or_default())
+                .collect();
+            let has_cpufreq = freqs.iter().any(|freq| freq.governor.is_some() || freq.max.is_some());
+
+            //powersave 调速器会拉低基准测试结果，突出显示
+            fn render_governor(args: RenderArgs) -> CombineString {
+                let RenderArgs { value, record, .. } = args;
+                if record.get("governor").map(|v| v.as_str()) != Some("powersave") {
+                    return value;
+                }
+                let mut value = match value {
+                    CombineString::AsStr(val) => val.normal(),
+                    CombineString::AsString(val) => val.normal(),
+                    CombineString::AsColoredString(val) => val,
+                };
+                value.fgcolor = Some(Color::Yellow);
+                value.style |= Styles::Bold;
+                CombineString::AsColoredString(value)
+            }
+
             let columns_details = vec![
                 Column {
                     title: "Name".to_string(),
@@ -167,15 +323,82 @@ impl SysInfo {
                     key: "cpu_usage".to_string(),
                     right_align: true,
                     color: Some(Color::Green),
+                    aggregate: Some(aggregate_percent),
+                    ..Column::default()
+                },
+                Column {
+                    title: "Min%".to_string(),
+                    key: "cpu_min".to_string(),
+                    hidden: samples < 2,
+                    right_align: true,
+                    color: Some(Color::Cyan),
+                    aggregate: Some(aggregate_percent),
+                    ..Column::default()
+                },
+                Column {
+                    title: "Max%".to_string(),
+                    key: "cpu_max".to_string(),
+                    hidden: samples < 2,
+                    right_align: true,
+                    color: Some(Color::Magenta),
+                    aggregate: Some(aggregate_percent),
+                    ..Column::default()
+                },
+                Column {
+                    title: "Usage".to_string(),
+                    key: "cpu_usage".to_string(),
+                    hidden: bar.is_none(),
+                    color: Some(Color::Green),
+                    thresholds: Some(self.thresholds),
+                    bar,
+                    aggregate: Some(aggregate_percent),
                     ..Column::default()
                 },
                 Column {
-                    title: "Frequency".to_string(),
+                    title: "Freq(MHz)".to_string(),
                     key: "frequency".to_string(),
                     right_align: true,
                     color: Some(Color::Yellow),
                     ..Column::default()
                 },
+                Column {
+                    title: "Min(MHz)".to_string(),
+                    key: "min_frequency".to_string(),
+                    hidden: !has_cpufreq,
+                    right_align: true,
+                    color: Some(Color::Cyan),
+                    ..Column::default()
+                },
+                Column {
+                    title: "Max(MHz)".to_string(),
+                    key: "max_frequency".to_string(),
+                    hidden: !has_cpufreq,
+                    right_align: true,
+                    color: Some(Color::Magenta),
+                    ..Column::default()
+                },
+                Column {
+                    title: "Governor".to_string(),
+                    key: "governor".to_string(),
+                    hidden: !has_cpufreq,
+                    color: Some(Color::BrightGreen),
+                    render: Some(render_governor),
+                    ..Column::default()
+                },
+                Column {
+                    title: "EPP".to_string(),
+                    key: "epp".to_string(),
+                    hidden: !has_cpufreq,
+                    color: Some(Color::BrightBlue),
+                    ..Column::default()
+                },
+                Column {
+                    title: "Boost".to_string(),
+                    key: "boost".to_string(),
+                    hidden: !has_cpufreq,
+                    color: Some(Color::BrightCyan),
+                    ..Column::default()
+                },
                 Column {
                     title: "VendorID".to_string(),
                     key: "vendor_id".to_string(),
@@ -191,31 +414,368 @@ impl SysInfo {
             ];
 
             let mut data_details = Vec::new();
-            for cpu in cpus {
+            for (index, cpu) in cpus.iter().enumerate() {
                 let name = cpu.name();
-                let cpu_usage = format!("{:.2}%", cpu.cpu_usage());
-                let frequency = format!("{}", cpu.frequency());
+                let (avg, min, max) = usage_stats(cpu_samples.get(index).map(|v| v.as_slice()).unwrap_or_default());
+                //优先使用 cpufreq 的当前频率
+                let freq = &freqs[index];
+                let frequency = format!("{}", freq.cur.unwrap_or(cpu.frequency()));
+                let to_text = |mhz: Option<u64>| mhz.map(|mhz| mhz.to_string()).unwrap_or_default();
+                let boost = match freq.boost {
+                    Some(true) => "on",
+                    Some(false) => "off",
+                    None => "",
+                };
                 let vendor_id = cpu.vendor_id();
                 let brand = cpu.brand();
 
                 data_details.push(HashMap::from([
                     ("name".to_string(), name.to_string()),
-                    ("cpu_usage".to_string(), cpu_usage),
+                    ("cpu_usage".to_string(), format!("{avg:.2}%")),
+                    ("cpu_min".to_string(), format!("{min:.2}%")),
+                    ("cpu_max".to_string(), format!("{max:.2}%")),
                     ("frequency".to_string(), frequency),
+                    ("min_frequency".to_string(), to_text(freq.min)),
+                    ("max_frequency".to_string(), to_text(freq.max)),
+                    ("governor".to_string(), freq.governor.clone().unwrap_or_default()),
+                    ("epp".to_string(), freq.epp.clone().unwrap_or_default()),
+                    ("boost".to_string(), boost.to_string()),
                     ("vendor_id".to_string(), vendor_id.to_string()),
                     ("brand".to_string(), brand.to_string()),
                 ]));
             }
 
-            let table_details = Table::new(columns_details, data_details, HashMap::new());
+            let mut table_details = Table::new(columns_details, data_details, HashMap::new());
+            if total {
+                table_details = table_details.with_footers(vec![Aggregate::Avg, Aggregate::Min, Aggregate::Max]);
+            }
             println!();
             println!();
             println!("{}", table_details);
         }
     }
 
+    /// 打印各 CPU 状态的时间占比，间隔 interval 读取两次 /proc/stat 计算
+    pub fn print_cpu_breakdown(&self, interval: Duration) {
+        let prev = read_stat(Path::new(PROC_STAT));
+        if prev.is_empty() {
+            eprintln!("{}", format!("Error reading {PROC_STAT}").red());
+            return;
+        }
+        std::thread::sleep(interval);
+        let cur = read_stat(Path::new(PROC_STAT));
+
+        let titles = ["User%", "Nice%", "System%", "Idle%", "IOWait%", "IRQ%", "SoftIRQ%", "Steal%", "Guest%"];
+        let colors = [Color::Green, Color::Cyan, Color::Yellow, Color::Blue, Color::Red, Color::Magenta, Color::BrightMagenta, Color::BrightRed, Color::BrightCyan];
+        let mut columns = vec![
+            Column {
+                title: "Name".to_string(),
+                key: "name".to_string(),
+                color: Some(Color::Red),
+                ..Column::default()
+            },
+        ];
+        for ((title, state), color) in titles.iter().zip(CPU_STATES).zip(colors) {
+            columns.push(Column {
+                title: title.to_string(),
+                key: state.to_string(),
+                right_align: true,
+                color: Some(color),
+                ..Column::default()
+            });
+        }
+
+        let mut data = Vec::new();
+        for times in &cur {
+            let Some(prev) = prev.iter().find(|p| p.name == times.name) else { continue };
+            let mut row = HashMap::from([("name".to_string(), times.name.clone())]);
+            for (state, percent) in CPU_STATES.iter().zip(times.breakdown(prev)) {
+                row.insert(state.to_string(), format!("{percent:.2}%"));
+            }
+            data.push(row);
+        }
+
+        let table = Table::new(columns, data, HashMap::new());
+        println!("{}", table);
+    }
+
+    /// 打印 CPU 拓扑: 插槽、物理核心、超线程兄弟、NUMA 节点和缓存大小，用于绑核
+    pub fn print_cpu_topology(&self) {
+        let topology = read_topology(Path::new(SYS_CPU), Path::new(SYS_NODE));
+        if topology.is_empty() {
+            eprintln!("{}", format!("Error reading {SYS_CPU}").red());
+            return;
+        }
+
+        let sockets: HashSet<Option<u32>> = topology.iter().map(|t| t.package).collect();
+        let cores: HashSet<(Option<u32>, Option<u32>)> = topology.iter().map(|t| (t.package, t.core)).collect();
+        let nodes: HashSet<u32> = topology.iter().filter_map(|t| t.node).collect();
+
+        let columns = vec![
+            Column {
+                title: "".to_string(),
+                key: "title".to_string(),
+                color: Some(Color::Red),
+                style: Style::default() | Styles::Bold,
+                ..Column::default()
+            },
+            Column {
+                title: "Sockets".to_string(),
+                key: "sockets".to_string(),
+                right_align: true,
+                color: Some(Color::Green),
+                ..Column::default()
+            },
+            Column {
+                title: "Cores".to_string(),
+                key: "cores".to_string(),
+                right_align: true,
+                color: Some(Color::Yellow),
+                ..Column::default()
+            },
+            Column {
+                title: "Threads".to_string(),
+                key: "threads".to_string(),
+                right_align: true,
+                color: Some(Color::Blue),
+                ..Column::default()
+            },
+            Column {
+                title: "NUMA".to_string(),
+                key: "nodes".to_string(),
+                right_align: true,
+                color: Some(Color::Magenta),
+                ..Column::default()
+            },
+        ];
+        let data = vec![HashMap::from([
+            ("title".to_string(), "CPU:".to_string()),
+            ("sockets".to_string(), sockets.len().to_string()),
+            ("cores".to_string(), cores.len().to_string()),
+            ("threads".to_string(), topology.len().to_string()),
+            ("nodes".to_string(), nodes.len().to_string()),
+        ])];
+        let table = Table::new(columns, data, HashMap::new());
+        println!("{}", table);
+
+        let mut columns_details = vec![
+            Column {
+                title: "CPU".to_string(),
+                key: "cpu".to_string(),
+                right_align: true,
+                color: Some(Color::Red),
+                ..Column::default()
+            },
+            Column {
+                title: "Socket".to_string(),
+                key: "socket".to_string(),
+                right_align: true,
+                color: Some(Color::Green),
+                ..Column::default()
+            },
+            Column {
+                title: "Core".to_string(),
+                key: "core".to_string(),
+                right_align: true,
+                color: Some(Color::Yellow),
+                ..Column::default()
+            },
+            Column {
+                title: "Siblings".to_string(),
+                key: "siblings".to_string(),
+                color: Some(Color::Blue),
+                ..Column::default()
+            },
+            Column {
+                title: "NUMA".to_string(),
+                key: "node".to_string(),
+                right_align: true,
+                color: Some(Color::Magenta),
+                ..Column::default()
+            },
+        ];
+        //只显示存在的缓存级别
+        let mut cache_names: Vec<&String> = topology.iter().flat_map(|t| t.caches.iter().map(|(name, _)| name)).collect();
+        cache_names.sort();
+        cache_names.dedup();
+        for name in cache_names {
+            columns_details.push(Column {
+                title: name.clone(),
+                key: name.clone(),
+                right_align: true,
+                color: Some(Color::Cyan),
+                ..Column::default()
+            });
+        }
+
+        let to_text = |id: Option<u32>| id.map(|id| id.to_string()).unwrap_or_else(|| "-".to_string());
+        let mut data_details = Vec::new();
+        for cpu in &topology {
+            let mut row = HashMap::from([
+                ("cpu".to_string(), cpu.cpu.to_string()),
+                ("socket".to_string(), to_text(cpu.package)),
+                ("core".to_string(), to_text(cpu.core)),
+                ("siblings".to_string(), cpu.siblings.clone()),
+                ("node".to_string(), to_text(cpu.node)),
+            ]);
+            row.extend(cpu.caches.iter().cloned());
+            data_details.push(row);
+        }
+
+        let table_details = Table::new(columns_details, data_details, HashMap::new());
+        println!();
+        println!();
+        println!("{}", table_details);
+    }
+
+    /// 打印指令集标志分组和 CPU 漏洞缓解状态
+    pub fn print_cpu_features(&self) {
+        let cpuinfo = fs::read_to_string(PROC_CPUINFO).unwrap_or_default();
+        let flags = parse_cpu_flags(&cpuinfo);
+        if flags.is_empty() {
+            eprintln!("{}", format!("Error reading {PROC_CPUINFO}").red());
+            return;
+        }
+
+        let cpu = self.system.cpus().first();
+        let vendor_id = cpu.map(|cpu| cpu.vendor_id()).unwrap_or_default();
+        let brand = cpu.map(|cpu| cpu.brand()).unwrap_or_default();
+        let width = 15;
+        println!("{:width$} {}", "VendorID:".color(Color::Red), vendor_id.color(Color::Blue));
+        println!("{:width$} {}", "Brand:".color(Color::Red), brand.color(Color::Magenta));
+        println!("{:width$} {}", "Flags:".color(Color::Red), flags.len().to_string().color(Color::Yellow));
+        println!();
+
+        let columns = vec![
+            Column {
+                title: "Group".to_string(),
+                key: "group".to_string(),
+                color: Some(Color::Red),
+                ..Column::default()
+            },
+            Column {
+                title: "Supported".to_string(),
+                key: "supported".to_string(),
+                color: Some(Color::Green),
+                ..Column::default()
+            },
+            Column {
+                title: "Missing".to_string(),
+                key: "missing".to_string(),
+                color: Some(Color::BrightBlack),
+                ..Column::default()
+            },
+        ];
+
+        let mut data = Vec::new();
+        for (group, names) in FEATURE_GROUPS {
+            let (supported, missing): (Vec<&str>, Vec<&str>) = names.iter().partition(|name| flags.iter().any(|flag| flag == *name));
+            data.push(HashMap::from([
+                ("group".to_string(), group.to_string()),
+                ("supported".to_string(), supported.join(" ")),
+                ("missing".to_string(), missing.join(" ")),
+            ]));
+        }
+        let table = Table::new(columns, data, HashMap::new());
+        println!("{}", table);
+
+        let vulnerabilities = read_vulnerabilities(Path::new(SYS_VULNERABILITIES));
+        if vulnerabilities.is_empty() {
+            return;
+        }
+
+        //未受影响显示绿色，已缓解显示黄色，存在漏洞显示红色
+        fn render_status(args: RenderArgs) -> CombineString {
+            let RenderArgs { value, record, .. } = args;
+            let status = record.get("status").map(|v| v.as_str()).unwrap_or_default();
+            let color = if status.starts_with("Not affected") {
+                Color::Green
+            } else if status.starts_with("Vulnerable") {
+                Color::Red
+            } else {
+                Color::Yellow
+            };
+            let mut value = match value {
+                CombineString::AsStr(val) => val.normal(),
+                CombineString::AsString(val) => val.normal(),
+                CombineString::AsColoredString(val) => val,
+            };
+            value.fgcolor = Some(color);
+            CombineString::AsColoredString(value)
+        }
+
+        let columns_vulnerabilities = vec![
+            Column {
+                title: "Vulnerability".to_string(),
+                key: "name".to_string(),
+                color: Some(Color::Red),
+                ..Column::default()
+            },
+            Column {
+                title: "Status".to_string(),
+                key: "status".to_string(),
+                render: Some(render_status),
+                ..Column::default()
+            },
+        ];
+
+        let mut data_vulnerabilities = Vec::new();
+        for (name, status) in vulnerabilities {
+            data_vulnerabilities.push(HashMap::from([
+                ("name".to_string(), name),
+                ("status".to_string(), status),
+            ]));
+        }
+        let table_vulnerabilities = Table::new(columns_vulnerabilities, data_vulnerabilities, HashMap::new());
+        println!();
+        println!();
+        println!("{}", table_vulnerabilities);
+    }
+
     /// 打印内存、交换分区信息
-    pub fn print_memory(&mut self) {
+    pub fn print_memory(&mut self, args: MemoryArgs) {
+        let MemoryArgs {
+            bar, details, human_readable, si, block_size, free, bytes, mebi, gibi, tebi, pebi, kilo, mega, giga, tera, peta,
+            wide, total, lohi, committed, seconds, count, swap_top, ..
+        } = args;
+        if free {
+            let unit = if human_readable {
+                FreeUnit::Human
+            } else if bytes {
+                FreeUnit::Bytes
+            } else if pebi || peta {
+                FreeUnit::Pebi
+            } else if tebi || tera {
+                FreeUnit::Tebi
+            } else if gibi || giga {
+                FreeUnit::Gibi
+            } else if mebi || mega {
+                FreeUnit::Mebi
+            } else {
+                FreeUnit::Kibi //-k 是默认单位
+            };
+            //--kilo、--mega 等是1000进制的单位
+            let si = si || kilo || mega || giga || tera || peta;
+            self.print_free(&FreeOptions { unit, si, wide, total, lohi, committed }, &seconds, count);
+            return;
+        }
+
+        let (base, block) = match parse_units(human_readable, si, &block_size) {
+            Ok(val) => val,
+            Err(err) => {
+                eprintln!("{err}: {block_size}");
+                return;
+            }
+        };
+
+        let bar = match parse_bar(&bar) {
+            Ok(val) => val,
+            Err(err) => {
+                eprintln!("{err}: {bar}");
+                return;
+            }
+        };
+
         let columns = vec![
             Column {
                 title: "".to_string(),
@@ -257,6 +817,16 @@ impl SysInfo {
                 key: "used_percent".to_string(),
                 right_align: true,
                 color: Some(Color::Cyan),
+                thresholds: Some(self.thresholds),
+                ..Column::default()
+            },
+            Column {
+                title: "Usage".to_string(),
+                key: "used_percent".to_string(),
+                hidden: bar.is_none(),
+                color: Some(Color::Cyan),
+                thresholds: Some(self.thresholds),
+                bar,
                 ..Column::default()
             },
         ];
@@ -267,10 +837,10 @@ impl SysInfo {
 
         self.system.refresh_memory_specifics(MemoryRefreshKind::new().with_ram());
 
-        let total = self.system.total_memory().pretty_size();
-        let used = self.system.used_memory().pretty_size();
-        let free = self.system.free_memory().pretty_size();
-        let available = self.system.available_memory().pretty_size();
+        let total = self.system.total_memory().pretty_size_with(base, block);
+        let used = self.system.used_memory().pretty_size_with(base, block);
+        let free = self.system.free_memory().pretty_size_with(base, block);
+        let available = self.system.available_memory().pretty_size_with(base, block);
         let used_percent = self.system.used_memory() as f64 / self.system.total_memory() as f64 * 100.0;
         let used_percent = format!("{:.2}%", used_percent);
 
@@ -287,11 +857,16 @@ impl SysInfo {
         // swap
         self.system.refresh_memory_specifics(MemoryRefreshKind::new().with_swap());
 
-        let total = self.system.total_swap().pretty_size();
-        let used = self.system.used_swap().pretty_size();
-        let free = self.system.free_swap().pretty_size();
-        let used_percent = self.system.used_swap() as f64 / self.system.total_swap() as f64 * 100.0;
-        let used_percent = format!("{:.2}%", used_percent);
+        let total = self.system.total_swap().pretty_size_with(base, block);
+        let used = self.system.used_swap().pretty_size_with(base, block);
+        let free = self.system.free_swap().pretty_size_with(base, block);
+        //没有交换分区时不计算百分比，避免显示 NaN%
+        let used_percent = if self.system.total_swap() == 0 {
+            "-".to_string()
+        } else {
+            let used_percent = self.system.used_swap() as f64 / self.system.total_swap() as f64 * 100.0;
+            format!("{:.2}%", used_percent)
+        };
 
         data.push(HashMap::from([
             ("title".to_string(), "Swap:".to_string()),
@@ -302,45 +877,289 @@ impl SysInfo {
             ("used_percent".to_string(), used_percent),
         ]));
 
+        //容器内的内存限制，只在比主机内存少时显示
+        let cgroup = read_cgroup_limits(Path::new(PROC_SELF_CGROUP), Path::new(SYS_FS_CGROUP)).unwrap_or_default();
+        if let Some(limit) = cgroup.memory_limit.filter(|limit| *limit < self.system.total_memory()) {
+            let usage = cgroup.memory_usage.unwrap_or_default();
+            let used_percent = usage as f64 / limit as f64 * 100.0;
+            data.push(HashMap::from([
+                ("title".to_string(), format!("Cgroup v{}:", cgroup.version)),
+                ("total".to_string(), limit.pretty_size_with(base, block)),
+                ("used".to_string(), usage.pretty_size_with(base, block)),
+                ("free".to_string(), limit.saturating_sub(usage).pretty_size_with(base, block)),
+                ("available".to_string(), "".to_string()),
+                ("used_percent".to_string(), format!("{:.2}%", used_percent)),
+            ]));
+        }
+        if let Some(limit) = cgroup.swap_limit.filter(|limit| *limit < self.system.total_swap()) {
+            data.push(HashMap::from([
+                ("title".to_string(), "Cgroup Swap:".to_string()),
+                ("total".to_string(), limit.pretty_size_with(base, block)),
+            ]));
+        }
+
         let table = Table::new(columns, data, HashMap::new());
         println!("{}", table);
+
+        self.print_swaps(base, block);
+        if swap_top > 0 {
+            self.print_swap_top(swap_top, base, block);
+        }
+        if details {
+            self.print_meminfo(base, block);
+        }
     }
 
-    pub fn print_disk(&self, cmd: Commands) {
-        let Commands::Disk { all, sort, exclude, total, human_readable, si, block_size, .. } = cmd else { todo!() };
-
-        let mut base: BaseSize = BaseSize::Size1024;
-        let mut block: BlockSize = BlockSize::Auto;
-        if human_readable {
-            base = BaseSize::Size1024;
-            block = BlockSize::Auto;
-        } else if si {
-            base = BaseSize::Size1000;
-            block = BlockSize::Auto;
-        }
-
-        if block_size.len() > 0 {
-            let result: Result<BlockSize, _> = block_size.parse();
-            block = match result {
-                Ok(val) => { val }
+    /// 打印 /proc/swaps 中的每个交换设备，没有交换设备时不打印
+    fn print_swaps(&self, base: BaseSize, block: BlockSize) {
+        let devices = read_swaps(Path::new(PROC_SWAPS));
+        if devices.is_empty() {
+            return;
+        }
+
+        let columns = vec![
+            Column {
+                title: "Swap Device".to_string(),
+                key: "name".to_string(),
+                color: Some(Color::Red),
+                ..Column::default()
+            },
+            Column {
+                title: "Type".to_string(),
+                key: "kind".to_string(),
+                color: Some(Color::Green),
+                ..Column::default()
+            },
+            Column {
+                title: "Size".to_string(),
+                key: "size".to_string(),
+                right_align: true,
+                color: Some(Color::Blue),
+                ..Column::default()
+            },
+            Column {
+                title: "Used".to_string(),
+                key: "used".to_string(),
+                right_align: true,
+                color: Some(Color::Yellow),
+                ..Column::default()
+            },
+            Column {
+                title: "Use%".to_string(),
+                key: "used_percent".to_string(),
+                right_align: true,
+                color: Some(Color::Cyan),
+                thresholds: Some(self.thresholds),
+                ..Column::default()
+            },
+            Column {
+                title: "Priority".to_string(),
+                key: "priority".to_string(),
+                right_align: true,
+                color: Some(Color::Magenta),
+                ..Column::default()
+            },
+        ];
+
+        let mut data = Vec::new();
+        for device in devices {
+            let used_percent = if device.size == 0 {
+                "-".to_string()
+            } else {
+                format!("{:.2}%", device.used as f64 / device.size as f64 * 100.0)
+            };
+            data.push(HashMap::from([
+                ("name".to_string(), device.name),
+                ("kind".to_string(), device.kind),
+                ("size".to_string(), device.size.pretty_size_with(base, block)),
+                ("used".to_string(), device.used.pretty_size_with(base, block)),
+                ("used_percent".to_string(), used_percent),
+                ("priority".to_string(), device.priority.to_string()),
+            ]));
+        }
+
+        let table = Table::new(columns, data, HashMap::new());
+        println!();
+        println!();
+        println!("{}", table);
+    }
+
+    /// 打印交换分区使用量最多的前 top 个进程
+    fn print_swap_top(&self, top: usize, base: BaseSize, block: BlockSize) {
+        let usage = swap_usage(Path::new(PROC));
+        println!();
+        println!();
+        if usage.is_empty() {
+            println!("{}", "No processes are using swap".green());
+            return;
+        }
+
+        let columns = vec![
+            Column {
+                title: "PID".to_string(),
+                key: "pid".to_string(),
+                right_align: true,
+                color: Some(Color::Red),
+                ..Column::default()
+            },
+            Column {
+                title: "Command".to_string(),
+                key: "command".to_string(),
+                color: Some(Color::Green),
+                ..Column::default()
+            },
+            Column {
+                title: "Swap".to_string(),
+                key: "swap".to_string(),
+                right_align: true,
+                color: Some(Color::Yellow),
+                ..Column::default()
+            },
+        ];
+
+        let mut data = Vec::new();
+        for (pid, command, swap) in usage.into_iter().take(top) {
+            data.push(HashMap::from([
+                ("pid".to_string(), pid.to_string()),
+                ("command".to_string(), command),
+                ("swap".to_string(), swap.pretty_size_with(base, block)),
+            ]));
+        }
+
+        let table = Table::new(columns, data, HashMap::new());
+        println!("{}", table);
+    }
+
+    /// 按 procps free(1) 的格式打印，供解析 free 输出的脚本使用，不着色
+    pub fn print_free(&self, options: &FreeOptions, seconds: &str, count: u32) {
+        let delay = if seconds.is_empty() {
+            None
+        } else {
+            match parse_duration(seconds) {
+                Ok(val) => Some(val),
                 Err(err) => {
-                    eprintln!("{err}: {block_size}");
+                    eprintln!("{err}: {seconds}");
                     return;
                 }
             }
+        };
+        //-c 不带 -s 时每秒重复一次，-s 不带 -c 时一直重复
+        let repeat = delay.is_some() || count > 0;
+        let delay = delay.unwrap_or(Duration::from_secs(1));
+
+        let mut printed = 0;
+        loop {
+            let stats = FreeStats::from_meminfo(&read_meminfo(Path::new(PROC_MEMINFO)));
+            print!("{}", format_free(&stats, options));
+            printed += 1;
+            if !repeat {
+                break;
+            }
+            println!();
+            if count > 0 && printed >= count {
+                break;
+            }
+            std::thread::sleep(delay);
+        }
+    }
+
+    /// 打印 /proc/meminfo 明细，区分缓存和应用程序的内存占用
+    fn print_meminfo(&self, base: BaseSize, block: BlockSize) {
+        let meminfo = read_meminfo(Path::new(PROC_MEMINFO));
+        let Some(&mem_total) = meminfo.get("MemTotal") else {
+            eprintln!("{}", format!("Error reading {PROC_MEMINFO}").red());
+            return;
+        };
+
+        let columns = vec![
+            Column {
+                title: "Name".to_string(),
+                key: "name".to_string(),
+                color: Some(Color::Red),
+                ..Column::default()
+            },
+            Column {
+                title: "Size".to_string(),
+                key: "size".to_string(),
+                right_align: true,
+                color: Some(Color::Green),
+                ..Column::default()
+            },
+            Column {
+                title: "%Total".to_string(),
+                key: "percent".to_string(),
+                right_align: true,
+                color: Some(Color::Cyan),
+                ..Column::default()
+            },
+            Column {
+                title: "Description".to_string(),
+                key: "description".to_string(),
+                color: Some(Color::BrightBlack),
+                ..Column::default()
+            },
+        ];
+
+        let mut data = Vec::new();
+        for (name, description) in MEMINFO_DETAILS {
+            let Some(&size) = meminfo.get(name) else { continue };
+            data.push(HashMap::from([
+                ("name".to_string(), name.to_string()),
+                ("size".to_string(), size.pretty_size_with(base, block)),
+                ("percent".to_string(), format!("{:.2}%", size as f64 / mem_total as f64 * 100.0)),
+                ("description".to_string(), description.to_string()),
+            ]));
+        }
+
+        let table = Table::new(columns, data, HashMap::new());
+        println!();
+        println!();
+        println!("{}", table);
+
+        //已提交内存接近 CommitLimit 时，严格超分配模式下分配会失败
+        if let (Some(&committed), Some(&limit)) = (meminfo.get("Committed_AS"), meminfo.get("CommitLimit")) {
+            if limit > 0 {
+                let percent = committed as f64 / limit as f64 * 100.0;
+                let text = format!("{:.2}%", percent).color(self.thresholds.color(percent));
+                println!();
+                println!("{} {} of {} ({})", "Committed:".color(Color::Red), committed.pretty_size_with(base, block), limit.pretty_size_with(base, block), text);
+            }
+        }
+    }
+
+    pub fn print_disk(&self, cmd: Commands) {
+        let Commands::Disk { all, sort, exclude, total, human_readable, si, block_size, bar, group_by, watch_mounts, json, busy, deleted, .. } = cmd else { todo!() };
+        if watch_mounts {
+            self.watch_mounts(json);
+            return;
+        }
+        if !busy.is_empty() {
+            self.print_busy(&busy);
+            return;
         }
 
+        let total = total || !group_by.is_empty(); //分组时总是生成合计行
+
+        let (base, block) = match parse_units(human_readable, si, &block_size) {
+            Ok(val) => val,
+            Err(err) => {
+                eprintln!("{err}: {block_size}");
+                return;
+            }
+        };
+
+        let bar = match parse_bar(&bar) {
+            Ok(val) => val,
+            Err(err) => {
+                eprintln!("{err}: {bar}");
+                return;
+            }
+        };
+
         // let render = |args: RenderArgs| -> CombineString {//closure-error: 无法解决
         fn render(args: RenderArgs) -> CombineString {
-            let RenderArgs { value, column, record_index, data, custom, .. } = args;
-            let mut total = false;
-            if let Some(val) = custom.get("total") {
-                if val == "true" {
-                    total = true;
-                }
-            }
-            let last = data.len() - 1;
-            if total && record_index == last {
+            let RenderArgs { value, column, record, .. } = args;
+            if is_subtotal(record) {
                 let mut value = match value {
                     CombineString::AsStr(val) => val.normal(),
                     CombineString::AsString(val) => val.normal(),
@@ -352,7 +1171,7 @@ impl SysInfo {
                 }
 
                 if value.fgcolor.is_none() {
-                    value.fgcolor = column.color;
+                    value.fgcolor = column.value_color(&value.input);
                 }
 
                 value.style |= Styles::Bold;
@@ -366,7 +1185,7 @@ impl SysInfo {
         let columns = vec![
             Column {
                 title: "Device".to_string(),
-                key: "name".to_string(),
+                key: "device".to_string(),
                 color: Some(Color::Red),
                 render: Some(render),
                 ..Column::default()
@@ -391,6 +1210,7 @@ impl SysInfo {
                 right_align: true,
                 color: Some(Color::Blue),
                 render: Some(render),
+                aggregate: Some(aggregate_disk_size),
                 ..Column::default()
             },
             Column {
@@ -399,6 +1219,7 @@ impl SysInfo {
                 right_align: true,
                 color: Some(Color::Magenta),
                 render: Some(render),
+                aggregate: Some(aggregate_disk_size),
                 ..Column::default()
             },
             Column {
@@ -407,6 +1228,7 @@ impl SysInfo {
                 right_align: true,
                 color: Some(Color::Cyan),
                 render: Some(render),
+                aggregate: Some(aggregate_disk_size),
                 ..Column::default()
             },
             Column {
@@ -416,6 +1238,7 @@ impl SysInfo {
                 right_align: true,
                 color: Some(Color::BrightRed),
                 render: Some(render),
+                aggregate: Some(aggregate_disk_size),
                 ..Column::default()
             },
             Column {
@@ -423,7 +1246,30 @@ impl SysInfo {
                 key: "usage_rate".to_string(),
                 right_align: true,
                 color: Some(Color::BrightGreen),
+                thresholds: Some(self.thresholds),
+                render: Some(render),
+                aggregate: Some(aggregate_disk_usage),
+                ..Column::default()
+            },
+            Column {
+                title: "Usage".to_string(),
+                key: "usage_rate".to_string(),
+                hidden: bar.is_none(),
+                color: Some(Color::BrightGreen),
+                thresholds: Some(self.thresholds),
+                bar,
+                render: Some(render),
+                aggregate: Some(aggregate_disk_usage),
+                ..Column::default()
+            },
+            Column {
+                title: "Deleted".to_string(),
+                key: "deleted_space".to_string(),
+                hidden: !deleted,
+                right_align: true,
+                color: Some(Color::Red),
                 render: Some(render),
+                aggregate: Some(aggregate_disk_size),
                 ..Column::default()
             },
             Column {
@@ -441,6 +1287,22 @@ impl SysInfo {
                 render: Some(render),
                 ..Column::default()
             },
+            Column {
+                title: "UUID".to_string(),
+                key: "uuid".to_string(),
+                color: Some(Color::BrightMagenta),
+                hidden: !all,
+                render: Some(render),
+                ..Column::default()
+            },
+            Column {
+                title: "Label".to_string(),
+                key: "label".to_string(),
+                color: Some(Color::BrightCyan),
+                hidden: !all,
+                render: Some(render),
+                ..Column::default()
+            },
         ];
 
         // FIELD:VALUE1,VALUE2
@@ -476,39 +1338,113 @@ impl SysInfo {
             }
         }
 
+        //FIELD: device 按物理磁盘分组，其他按列标题分组
+        let mut group_key: &str = "";
+        if !group_by.is_empty() && !group_by.eq_ignore_ascii_case("device") {
+            for col in &columns {
+                if group_by == col.title {
+                    group_key = &col.key;
+                    break;
+                }
+            }
+            if group_key.is_empty() {
+                let err = format!("Invalid group-by: {}", group_by).red();
+                eprintln!("{}", err);
+                return;
+            }
+        }
+
         let mut data = Vec::new();
         let disks = Disks::new_with_refreshed_list();
-        let mut total_total = 0;
-        let mut total_used = 0;
-        let mut total_free = 0;
-        let mut total_avail = 0;
-        for disk in &disks {
+        let uuids = device_links(&Path::new(DEV_DISK).join("by-uuid"));
+        let labels = device_links(&Path::new(DEV_DISK).join("by-label"));
+        let degraded: HashSet<String> = read_md_arrays(Path::new(PROC_MDSTAT), Path::new(SYS_BLOCK))
+            .into_iter().filter(|array| array.degraded).map(|array| array.name).collect();
+
+        //已删除但仍被打开的文件按设备号对应到挂载点，解释 df 和 du 结果不一致
+        let deleted_list = if deleted { deleted_files(Path::new(PROC)) } else { Vec::new() };
+        let deleted_by_dev = deleted_space(&deleted_list);
+        let mut mount_devs: HashMap<u64, String> = HashMap::new();
+
+        //同一设备挂载多次时与 df 相同，保留挂载路径最短的一个，长度相同时保留先出现的
+        let mut shortest: HashMap<&OsStr, usize> = HashMap::new();
+        for (index, disk) in disks.iter().enumerate() {
+            let len = disk.mount_point().as_os_str().len();
+            let kept = shortest.entry(disk.name()).or_insert(index);
+            if len < disks[*kept].mount_point().as_os_str().len() {
+                *kept = index;
+            }
+        }
+
+        for (index, disk) in disks.iter().enumerate() {
             let kind: String = disk.kind().to_string();
             let name: String = disk.name().to_str().unwrap_or_default().to_string();
             let file_system: String = disk.file_system().to_str().unwrap_or_default().to_string();
+
+            //默认隐藏伪文件系统、虚拟文件系统和重复挂载的设备(bind mount等)，--all 显示全部
+            let duplicate = name.starts_with('/') && shortest.get(disk.name()) != Some(&index);
+            if !all && (duplicate || fs_class(&file_system) != FsClass::Regular) {
+                continue;
+            }
+
+            //loop设备显示后端文件，dm设备显示LVM/crypt名称
+            let mut device = match device_label(&name) {
+                Some(label) => format!("{name} ({label})"),
+                None => name.clone(),
+            };
+
+            //由降级的软RAID阵列承载的设备加上警告标记
+            if !degraded.is_empty() {
+                let md = kernel_name(&name).and_then(|kname| degraded_array(Path::new(SYS_CLASS_BLOCK), &kname, &degraded));
+                if let Some(md) = md {
+                    device = format!("{device} [{md} degraded]");
+                }
+            }
+
             let mount_point: String = disk.mount_point().to_str().unwrap_or_default().to_string();
             let total_space: String = disk.total_space().pretty_size_with(base, block);
             let available_space: String = disk.available_space().pretty_size_with(base, block);
             let is_removable: String = disk.is_removable().to_string();
+            let uuid: String = device_link(&uuids, &name).unwrap_or_default();
+            let label: String = device_link(&labels, &name).unwrap_or_default();
+
+            let mut deleted_size: u64 = 0;
+            if deleted {
+                if let Ok(meta) = fs::metadata(&mount_point) {
+                    deleted_size = deleted_by_dev.get(&meta.dev()).copied().unwrap_or_default();
+                    mount_devs.entry(meta.dev()).or_insert_with(|| mount_point.clone());
+                }
+            }
+            let deleted_space: String = deleted_size.pretty_size_with(base, block);
 
             let mut free_size: u64 = 0;
+            let mut stale = false;
             let disk_info_result = disk_info(&mount_point);
             match disk_info_result {
                 Ok(res) => free_size = res.f_bfree * res.f_bsize,
+                Err(StatvfsError::PermissionDenied { .. }) => continue, //没有权限的挂载点静默跳过
+                Err(StatvfsError::Stale { .. }) => stale = true, //ESTALE: 失效的挂载点(如断开的NFS)单独标记
                 Err(err) => {
-                    eprintln!("print_disk disk_info error: {}", err.red())
+                    eprintln!("print_disk disk_info error: {}", err.to_string().red())
                 }
             }
-            let free_space: String = free_size.pretty_size_with(base, block);
+            let mut free_space: String = free_size.pretty_size_with(base, block);
 
             let used_size = disk.total_space() - free_size;
-            let used_space = used_size.pretty_size_with(base, block);
+            let mut used_space = used_size.pretty_size_with(base, block);
 
             let usage_rate_num = used_size as f64 / disk.total_space() as f64 * 100.;
-            let usage_rate = format!("{usage_rate_num:.2}%");
+            let mut usage_rate = format!("{usage_rate_num:.2}%");
 
-            let row = HashMap::from([
-                ("name".to_string(), name),
+            if stale {
+                free_space = "-".to_string();
+                used_space = "-".to_string();
+                usage_rate = "stale".to_string();
+            }
+
+            let mut row = HashMap::from([
+                ("device".to_string(), device),
+                ("name".to_string(), name.clone()), //额外增加，仅排除用
                 ("file_system".to_string(), file_system),
                 ("kind".to_string(), kind),
                 ("total_space".to_string(), total_space),
@@ -520,10 +1456,24 @@ impl SysInfo {
                 ("available_space".to_string(), available_space),
                 ("available".to_string(), disk.available_space().to_string()), //额外增加，仅排序用
                 ("usage_rate".to_string(), usage_rate),
+                ("deleted_space".to_string(), deleted_space),
+                ("deleted".to_string(), deleted_size.to_string()), //额外增加，仅排序用
                 ("mount_point".to_string(), mount_point),
                 ("is_removable".to_string(), is_removable),
+                ("uuid".to_string(), uuid),
+                ("label".to_string(), label),
+                ("stale".to_string(), stale.to_string()), //额外增加，仅合计用
             ]);
 
+            if !group_by.is_empty() {
+                let group = if !group_key.is_empty() {
+                    row.get(group_key).cloned().unwrap_or_default()
+                } else {
+                    kernel_name(&name).map(|kname| physical_device(Path::new(SYS_CLASS_BLOCK), &kname)).unwrap_or(name)
+                };
+                row.insert("group".to_string(), group);
+            }
+
             let mut exclude_flag = false;
             if exclude_key.len() > 0 && exclude_vals.len() > 0 {
                 exclude_flag = exclude_record_disk(exclude_key, &exclude_vals, &row);
@@ -531,12 +1481,6 @@ impl SysInfo {
 
             if !exclude_flag {
                 data.push(row);
-                if total {
-                    total_total += disk.total_space();
-                    total_used += used_size;
-                    total_free += free_size;
-                    total_avail += disk.available_space();
-                }
             }
         }
 
@@ -549,17 +1493,13 @@ impl SysInfo {
                 }
             }
 
-            let last = key.len() - "_space".len();
             if key.len() > 0 {
                 data.sort_by(|a, b| {
-                    let mut key = key;
-                    if key.ends_with("_space") {
-                        key = &key[..last]
-                    }
+                    let key = key.strip_suffix("_space").unwrap_or(key);
                     let empty = &"".to_string();
                     let val_a = a.get(key).unwrap_or(empty);
                     let val_b = b.get(key).unwrap_or(empty);
-                    if key == "total" || key == "used" || key == "free" || key == "available" {
+                    if key == "total" || key == "used" || key == "free" || key == "available" || key == "deleted" {
                         let u64_a: u64 = val_a.parse().unwrap_or_default();
                         let u64_b: u64 = val_b.parse().unwrap_or_default();
                         u64_a.cmp(&u64_b)
@@ -576,38 +1516,695 @@ impl SysInfo {
             }
         }
 
+        //分组: 稳定排序保留组内的排序结果，每组后面插入小计行
+        if !group_by.is_empty() {
+            data.sort_by(|a, b| a.get("group").cmp(&b.get("group")));
+            let mut grouped = Vec::with_capacity(data.len() * 2);
+            let mut start = 0;
+            for end in 1..=data.len() {
+                if end == data.len() || data[end].get("group") != data[start].get("group") {
+                    let rows: Vec<&HashMap<String, String>> = data[start..end].iter().collect();
+                    let label = format!("{} subtotal", rows[0].get("group").cloned().unwrap_or_default());
+                    let mut subtotal = aggregate_row(&columns, &rows, Aggregate::Sum, base, block);
+                    subtotal.insert("device".to_string(), label);
+                    subtotal.insert("subtotal".to_string(), "true".to_string());
+                    grouped.extend(data[start..end].iter().cloned());
+                    grouped.push(subtotal);
+                    start = end;
+                }
+            }
+            data = grouped;
+        }
+
+        let mut table = Table::new(columns, data, HashMap::new()).with_units(base, block);
         if total {
-            let total_usage = total_used as f64 / total_total as f64 * 100.;
-            let total_usage_rate = format!("{total_usage:.2}%");
+            table = table.with_footers(vec![Aggregate::Sum]);
+        }
+        println!("{}", table);
+
+        if deleted {
+            self.print_deleted(&deleted_list, &mount_devs, base, block);
+        }
+    }
+
+    /// 打印已删除但仍被打开的文件明细，按挂载点分组、文件大小从大到小排列
+    fn print_deleted(&self, files: &[DeletedFile], mount_devs: &HashMap<u64, String>, base: BaseSize, block: BlockSize) {
+        let mut files: Vec<(&String, &DeletedFile)> = files.iter()
+            .filter_map(|file| Some((mount_devs.get(&file.dev)?, file)))
+            .collect();
+        if files.is_empty() {
+            println!("{}", "No deleted files are held open".green());
+            return;
+        }
+        files.sort_by(|a, b| a.0.cmp(b.0).then(b.1.size.cmp(&a.1.size)));
+
+        let columns = vec![
+            Column {
+                title: "MountPoint".to_string(),
+                key: "mount_point".to_string(),
+                color: Some(Color::BrightYellow),
+                ..Column::default()
+            },
+            Column {
+                title: "PID".to_string(),
+                key: "pid".to_string(),
+                right_align: true,
+                color: Some(Color::Red),
+                ..Column::default()
+            },
+            Column {
+                title: "Command".to_string(),
+                key: "command".to_string(),
+                color: Some(Color::Green),
+                ..Column::default()
+            },
+            Column {
+                title: "FD".to_string(),
+                key: "fd".to_string(),
+                right_align: true,
+                color: Some(Color::Yellow),
+                ..Column::default()
+            },
+            Column {
+                title: "Size".to_string(),
+                key: "size".to_string(),
+                right_align: true,
+                color: Some(Color::Blue),
+                ..Column::default()
+            },
+            Column {
+                title: "Apparent".to_string(),
+                key: "apparent_size".to_string(),
+                right_align: true,
+                color: Some(Color::Cyan),
+                ..Column::default()
+            },
+            Column {
+                title: "Path".to_string(),
+                key: "path".to_string(),
+                color: Some(Color::Magenta),
+                ..Column::default()
+            },
+        ];
+
+        let mut data = Vec::new();
+        for (mount_point, file) in files {
             data.push(HashMap::from([
-                ("name".to_string(), "total".to_string()),
-                ("total_space".to_string(), total_total.pretty_size_with(base, block)),
-                ("used_space".to_string(), total_used.pretty_size_with(base, block)),
-                ("free_space".to_string(), total_free.pretty_size_with(base, block)),
-                ("available_space".to_string(), total_avail.pretty_size_with(base, block)),
-                ("usage_rate".to_string(), total_usage_rate),
+                ("mount_point".to_string(), mount_point.clone()),
+                ("pid".to_string(), file.pid.to_string()),
+                ("command".to_string(), file.command.clone()),
+                ("fd".to_string(), file.fd.to_string()),
+                ("size".to_string(), file.size.pretty_size_with(base, block)),
+                ("apparent_size".to_string(), file.apparent_size.pretty_size_with(base, block)),
+                ("path".to_string(), file.path.clone()),
             ]));
         }
 
-        let custom = HashMap::from([
-            ("total".to_string(), total.to_string()),
-        ]);
-        let table = Table::new(columns, data, custom);
+        let table = Table::new(columns, data, HashMap::new());
         println!("{}", table);
     }
-}
 
-fn exclude_record_disk(exclude_key: &str, exclude_vals: &Vec<&str>, row: &HashMap<String, String>) -> bool {
-    let val_opt = row.get(exclude_key);
-    return if let Some(val) = val_opt {
-        for ev in exclude_vals {
-            if ev == val {
-                return true;
+    /// 监视挂载表变化，打印新增、卸载和重新挂载的文件系统，直到 Ctrl-C
+    pub fn watch_mounts(&self, json: bool) {
+        let mut file = match File::open(PROC_SELF_MOUNTINFO) {
+            Ok(file) => file,
+            Err(err) => {
+                eprintln!("{}", format!("Error opening {PROC_SELF_MOUNTINFO}: {err}").red());
+                return;
+            }
+        };
+        let mut mounts = match read_mounts(&mut file) {
+            Ok(mounts) => mounts,
+            Err(err) => {
+                eprintln!("{}", format!("Error reading {PROC_SELF_MOUNTINFO}: {err}").red());
+                return;
+            }
+        };
+        if !json {
+            println!("Watching {} mounts for changes (Ctrl-C to stop)", mounts.len());
+        }
+
+        loop {
+            if let Err(err) = wait_change(&file) {
+                eprintln!("{}", format!("Error polling {PROC_SELF_MOUNTINFO}: {err}").red());
+                return;
+            }
+            let new_mounts = match read_mounts(&mut file) {
+                Ok(mounts) => mounts,
+                Err(err) => {
+                    eprintln!("{}", format!("Error reading {PROC_SELF_MOUNTINFO}: {err}").red());
+                    return;
+                }
+            };
+
+            //JSON 使用带时区偏移的 RFC 3339 时间，便于其他程序解析
+            let time = if json { format_rfc3339(now_secs()) } else { format_local_time(now_secs()) };
+            for event in diff_mounts(&mounts, &new_mounts) {
+                if json {
+                    println!("{}", mount_event_json(&time, &event));
+                } else {
+                    println!("{}", mount_event_text(&time, &event));
+                }
+            }
+            mounts = new_mounts;
+        }
+    }
+
+    /// 打印正在使用指定挂载点上文件的进程，即 umount 时 "target is busy" 的原因
+    pub fn print_busy(&self, mount_point: &str) {
+        //子目录的 st_dev 与所在文件系统相同，只接受挂载点，避免误报整个文件系统
+        let path = match fs::canonicalize(mount_point) {
+            Ok(path) => path.to_string_lossy().to_string(),
+            Err(err) => {
+                eprintln!("{}", format!("Invalid mount point {mount_point}: {err}").red());
+                return;
+            }
+        };
+        let mounts = fs::read_to_string(PROC_SELF_MOUNTINFO).map(|text| parse_mountinfo(&text)).unwrap_or_default();
+        if !is_mount_point(&mounts, &path) {
+            eprintln!("{}", format!("{mount_point} is not a mount point").red());
+            return;
+        }
+
+        let dev = match fs::metadata(mount_point) {
+            Ok(meta) => meta.dev(),
+            Err(err) => {
+                eprintln!("{}", format!("Invalid mount point {mount_point}: {err}").red());
+                return;
+            }
+        };
+
+        let files = busy_files(Path::new(PROC), dev);
+        if files.is_empty() {
+            println!("{}", format!("No processes are using {mount_point}").green());
+            return;
+        }
+
+        let columns = vec![
+            Column {
+                title: "PID".to_string(),
+                key: "pid".to_string(),
+                right_align: true,
+                color: Some(Color::Red),
+                ..Column::default()
+            },
+            Column {
+                title: "Command".to_string(),
+                key: "command".to_string(),
+                color: Some(Color::Green),
+                ..Column::default()
+            },
+            Column {
+                title: "Access".to_string(),
+                key: "access".to_string(),
+                color: Some(Color::Yellow),
+                ..Column::default()
+            },
+            Column {
+                title: "Path".to_string(),
+                key: "path".to_string(),
+                color: Some(Color::BrightYellow),
+                ..Column::default()
+            },
+        ];
+
+        let mut data = Vec::new();
+        for file in files {
+            data.push(HashMap::from([
+                ("pid".to_string(), file.pid.to_string()),
+                ("command".to_string(), file.command),
+                ("access".to_string(), file.access),
+                ("path".to_string(), file.path),
+            ]));
+        }
+
+        let table = Table::new(columns, data, HashMap::new());
+        println!("{}", table);
+    }
+
+    /// 打印 CPU、内存和 IO 的压力停顿信息(PSI)，avg 按阈值着色
+    pub fn print_pressure(&self) {
+        let thresholds = self.thresholds;
+
+        let columns = vec![
+            Column {
+                title: "Resource".to_string(),
+                key: "resource".to_string(),
+                color: Some(Color::Red),
+                ..Column::default()
+            },
+            Column {
+                title: "Kind".to_string(),
+                key: "kind".to_string(),
+                color: Some(Color::Blue),
+                ..Column::default()
+            },
+            Column {
+                title: "Avg10".to_string(),
+                key: "avg10".to_string(),
+                right_align: true,
+                thresholds: Some(thresholds),
+                ..Column::default()
+            },
+            Column {
+                title: "Avg60".to_string(),
+                key: "avg60".to_string(),
+                right_align: true,
+                thresholds: Some(thresholds),
+                ..Column::default()
+            },
+            Column {
+                title: "Avg300".to_string(),
+                key: "avg300".to_string(),
+                right_align: true,
+                thresholds: Some(thresholds),
+                ..Column::default()
+            },
+            Column {
+                title: "Total".to_string(),
+                key: "total".to_string(),
+                right_align: true,
+                color: Some(Color::Magenta),
+                ..Column::default()
+            },
+        ];
+
+        let mut data = Vec::new();
+        let mut errors = Vec::new();
+        for resource in PRESSURE_RESOURCES {
+            let lines = match read_pressure(Path::new(PROC_PRESSURE), resource) {
+                Ok(lines) => lines,
+                Err(err) if is_unavailable(&err) => continue,
+                Err(err) => {
+                    errors.push(format!("Error reading {PROC_PRESSURE}/{resource}: {err}"));
+                    continue;
+                }
+            };
+            for line in lines {
+                data.push(HashMap::from([
+                    ("resource".to_string(), resource.to_string()),
+                    ("kind".to_string(), line.kind),
+                    ("avg10".to_string(), format!("{:.2}%", line.avg10)),
+                    ("avg60".to_string(), format!("{:.2}%", line.avg60)),
+                    ("avg300".to_string(), format!("{:.2}%", line.avg300)),
+                    ("total".to_string(), format!("{:.2}s", line.total as f64 / 1_000_000.)),
+                ]));
+            }
+        }
+
+        //全部资源都读不到时只提示一次，部分资源出错时才逐个打印错误
+        if data.is_empty() {
+            let msg = format!("Pressure stall information is not available: {PROC_PRESSURE} is missing or disabled (kernel without CONFIG_PSI or booted with psi=0)");
+            println!("{}", msg.yellow());
+            return;
+        }
+        for err in errors {
+            eprintln!("{}", err.red());
+        }
+
+        let table = Table::new(columns, data, HashMap::new());
+        println!("{}", table);
+    }
+
+    /// 打印中断和软中断的每秒次数(按 CPU 和中断源)、中断亲和性和上下文切换次数
+    pub fn print_irq(&self, interval: &str, all: bool) {
+        let interval = match parse_duration(interval) {
+            Ok(val) if !val.is_zero() => val,
+            _ => {
+                eprintln!("Invalid Duration: {interval}");
+                return;
+            }
+        };
+
+        let (_, old_irqs) = read_interrupts(Path::new(PROC_INTERRUPTS));
+        let (_, old_softirqs) = read_interrupts(Path::new(PROC_SOFTIRQS));
+        let old_stat = parse_stat_counters(&fs::read_to_string(PROC_STAT).unwrap_or_default());
+        std::thread::sleep(interval);
+        let (cpus, new_irqs) = read_interrupts(Path::new(PROC_INTERRUPTS));
+        let (softirq_cpus, new_softirqs) = read_interrupts(Path::new(PROC_SOFTIRQS));
+        let new_stat = parse_stat_counters(&fs::read_to_string(PROC_STAT).unwrap_or_default());
+        if cpus.is_empty() {
+            eprintln!("{}", format!("Error reading {PROC_INTERRUPTS}").red());
+            return;
+        }
+
+        let secs = interval.as_secs_f64();
+        let rate = |count: u64| format!("{:.0}", count as f64 / secs);
+
+        let width = 20;
+        println!("{:width$} {}", "Context switches/s:".color(Color::Red), rate(new_stat.0.saturating_sub(old_stat.0)).color(Color::Green));
+        println!("{:width$} {}", "Interrupts/s:".color(Color::Red), rate(new_stat.1.saturating_sub(old_stat.1)).color(Color::Yellow));
+        println!();
+
+        //中断按每秒总次数从大到小排列，默认隐藏采样期间没有发生的中断
+        let mut irqs = diff_interrupts(&old_irqs, &new_irqs);
+        irqs.sort_by_key(|irq| std::cmp::Reverse(irq.total()));
+        if !all {
+            irqs.retain(|irq| irq.total() > 0);
+        }
+
+        let mut columns = vec![
+            Column {
+                title: "IRQ".to_string(),
+                key: "name".to_string(),
+                right_align: true,
+                color: Some(Color::Red),
+                ..Column::default()
+            },
+        ];
+        columns.extend(irq_cpu_columns(&cpus));
+        columns.extend([
+            Column {
+                title: "Total/s".to_string(),
+                key: "total".to_string(),
+                right_align: true,
+                color: Some(Color::Green),
+                style: Style::default() | Styles::Bold,
+                ..Column::default()
+            },
+            Column {
+                title: "Affinity".to_string(),
+                key: "affinity".to_string(),
+                color: Some(Color::Yellow),
+                ..Column::default()
+            },
+            Column {
+                title: "Description".to_string(),
+                key: "description".to_string(),
+                color: Some(Color::Magenta),
+                ..Column::default()
+            },
+        ]);
+
+        let mut data = Vec::new();
+        for irq in &irqs {
+            let mut row = irq_cpu_row(irq, &cpus, &rate);
+            row.insert("affinity".to_string(), irq_affinity(Path::new(PROC_IRQ), &irq.name).unwrap_or_default());
+            row.insert("description".to_string(), irq.description.clone());
+            data.push(row);
+        }
+        let table = Table::new(columns, data, HashMap::new());
+        println!("{}", table);
+
+        let softirqs = diff_interrupts(&old_softirqs, &new_softirqs);
+        if softirqs.is_empty() {
+            return;
+        }
+        let mut columns_softirqs = vec![
+            Column {
+                title: "SoftIRQ".to_string(),
+                key: "name".to_string(),
+                color: Some(Color::Red),
+                ..Column::default()
+            },
+        ];
+        columns_softirqs.extend(irq_cpu_columns(&softirq_cpus));
+        columns_softirqs.push(Column {
+            title: "Total/s".to_string(),
+            key: "total".to_string(),
+            right_align: true,
+            color: Some(Color::Green),
+            style: Style::default() | Styles::Bold,
+            ..Column::default()
+        });
+
+        let data_softirqs = softirqs.iter().map(|irq| irq_cpu_row(irq, &softirq_cpus, &rate)).collect();
+        let table_softirqs = Table::new(columns_softirqs, data_softirqs, HashMap::new());
+        println!();
+        println!();
+        println!("{}", table_softirqs);
+    }
+
+    /// 打印软RAID(md)阵列状态
+    pub fn print_md(&self) {
+        let arrays = read_md_arrays(Path::new(PROC_MDSTAT), Path::new(SYS_BLOCK));
+        if arrays.is_empty() {
+            println!("{}", "No md arrays found".yellow());
+            return;
+        }
+
+        //降级阵列整行标红
+        fn render(args: RenderArgs) -> CombineString {
+            let RenderArgs { value, record, .. } = args;
+            if record.get("degraded").map(|v| v.as_str()) != Some("yes") {
+                return value;
+            }
+            let mut value = match value {
+                CombineString::AsStr(val) => val.normal(),
+                CombineString::AsString(val) => val.normal(),
+                CombineString::AsColoredString(val) => val,
+            };
+            value.fgcolor = Some(Color::Red);
+            value.style |= Styles::Bold;
+            CombineString::AsColoredString(value)
+        }
+
+        let columns = vec![
+            Column {
+                title: "Array".to_string(),
+                key: "name".to_string(),
+                color: Some(Color::Red),
+                render: Some(render),
+                ..Column::default()
+            },
+            Column {
+                title: "State".to_string(),
+                key: "state".to_string(),
+                color: Some(Color::Green),
+                render: Some(render),
+                ..Column::default()
+            },
+            Column {
+                title: "Level".to_string(),
+                key: "level".to_string(),
+                color: Some(Color::Yellow),
+                render: Some(render),
+                ..Column::default()
+            },
+            Column {
+                title: "Disks".to_string(),
+                key: "disks".to_string(),
+                right_align: true,
+                color: Some(Color::Blue),
+                render: Some(render),
+                ..Column::default()
+            },
+            Column {
+                title: "Status".to_string(),
+                key: "status".to_string(),
+                color: Some(Color::Magenta),
+                render: Some(render),
+                ..Column::default()
+            },
+            Column {
+                title: "Degraded".to_string(),
+                key: "degraded".to_string(),
+                color: Some(Color::Cyan),
+                render: Some(render),
+                ..Column::default()
+            },
+            Column {
+                title: "Sync".to_string(),
+                key: "sync".to_string(),
+                color: Some(Color::BrightYellow),
+                render: Some(render),
+                ..Column::default()
+            },
+            Column {
+                title: "Devices".to_string(),
+                key: "members".to_string(),
+                color: Some(Color::BrightBlue),
+                render: Some(render),
+                ..Column::default()
+            },
+        ];
+
+        let mut data = Vec::new();
+        for array in &arrays {
+            let mut state = if array.active { "active" } else { "inactive" }.to_string();
+            if array.read_only {
+                state.push_str(" (ro)");
+            }
+            let disks = if array.raid_disks > 0 {
+                format!("{}/{}", array.active_disks, array.raid_disks)
+            } else {
+                String::new()
+            };
+
+            data.push(HashMap::from([
+                ("name".to_string(), array.name.clone()),
+                ("state".to_string(), state),
+                ("level".to_string(), array.level.clone()),
+                ("disks".to_string(), disks),
+                ("status".to_string(), array.status.clone()),
+                ("degraded".to_string(), if array.degraded { "yes" } else { "no" }.to_string()),
+                ("sync".to_string(), array.sync_text()),
+                ("members".to_string(), array.members_text()),
+            ]));
+        }
+
+        let table = Table::new(columns, data, HashMap::new());
+        println!("{}", table);
+    }
+}
+
+/// 挂载事件的文本格式: 时间 事件 挂载点 设备 类型 选项
+fn mount_event_text(time: &str, event: &MountEvent) -> String {
+    let MountEvent { kind, mount, old_options, old_super_options } = event;
+    let color = match kind {
+        MountEventKind::Added => Color::Green,
+        MountEventKind::Removed => Color::Red,
+        MountEventKind::Remounted => Color::Yellow,
+    };
+    let mut options = match old_options {
+        Some(old) if *old != mount.mount_options => format!("{old} -> {}", mount.mount_options),
+        _ => mount.mount_options.clone(),
+    };
+    if let Some(old) = old_super_options.as_ref().filter(|old| **old != mount.super_options) {
+        options = format!("{options}  super: {old} -> {}", mount.super_options);
+    }
+    format!("{}  {}  {}  {}  {}  {}",
+            time,
+            format!("{:<9}", kind.as_str()).color(color).bold(),
+            mount.mount_point.color(Color::BrightYellow),
+            mount.source.color(Color::Red),
+            mount.fs_type.color(Color::Green),
+            options.color(Color::Cyan))
+}
+
+/// 挂载事件的 JSON 格式(每个事件一行)
+fn mount_event_json(time: &str, event: &MountEvent) -> String {
+    let MountEvent { kind, mount, old_options, old_super_options } = event;
+    let old_or_null = |old: &Option<String>| match old {
+        Some(old) => json_string(old),
+        None => "null".to_string(),
+    };
+    format!(r#"{{"time":{},"event":{},"mount_id":{},"mount_point":{},"source":{},"fs_type":{},"options":{},"old_options":{},"super_options":{},"old_super_options":{}}}"#,
+            json_string(time),
+            json_string(kind.as_str()),
+            mount.mount_id,
+            json_string(&mount.mount_point),
+            json_string(&mount.source),
+            json_string(&mount.fs_type),
+            json_string(&mount.mount_options),
+            old_or_null(old_options),
+            json_string(&mount.super_options),
+            old_or_null(old_super_options))
+}
+
+/// 合计行中参与计算的磁盘行: 排除失效(stale)的挂载点和分组小计行
+fn disk_rows<'a>(rows: &[&'a HashMap<String, String>]) -> Vec<&'a HashMap<String, String>> {
+    rows.iter()
+        .filter(|row| row.get("stale").map(|v| v.as_str()) != Some("true") && !is_subtotal(row))
+        .copied()
+        .collect()
+}
+
+/// 磁盘大小列(Total/Used/Free/Avail)的聚合函数，使用排序用的原始字节数
+fn aggregate_disk_size(args: AggregateArgs) -> String {
+    let AggregateArgs { aggregate, column, rows, base, block } = args;
+    let key = column.key.strip_suffix("_space").unwrap_or(&column.key);
+    match aggregate_values(aggregate, &disk_rows(rows), key) {
+        Some(val) => (val as u64).pretty_size_with(base, block),
+        None => String::new(),
+    }
+}
+
+/// 磁盘 Use% 列的聚合函数，合计时按 已用/总量 计算，而不是百分比相加
+fn aggregate_disk_usage(args: AggregateArgs) -> String {
+    let AggregateArgs { aggregate, column, rows, base, block } = args;
+    let rows = disk_rows(rows);
+    if aggregate != Aggregate::Sum {
+        return aggregate_percent(AggregateArgs { aggregate, column, rows: &rows, base, block });
+    }
+
+    let used = aggregate_values(Aggregate::Sum, &rows, "used").unwrap_or_default();
+    let total = aggregate_values(Aggregate::Sum, &rows, "total").unwrap_or_default();
+    //没有可统计的行或全部是大小为0的文件系统
+    if total == 0. {
+        return "-".to_string();
+    }
+    let usage = used / total * 100.;
+    format!("{usage:.2}%")
+}
+
+/// 中断表中每个 CPU 一列
+fn irq_cpu_columns(cpus: &[String]) -> Vec<Column> {
+    cpus.iter().map(|cpu| Column {
+        title: cpu.clone(),
+        key: cpu.clone(),
+        right_align: true,
+        color: Some(Color::Cyan),
+        ..Column::default()
+    }).collect()
+}
+
+/// 中断表的一行: 名称、每个 CPU 的每秒次数和合计
+fn irq_cpu_row(irq: &IrqCounts, cpus: &[String], rate: &dyn Fn(u64) -> String) -> HashMap<String, String> {
+    let mut row = HashMap::from([
+        ("name".to_string(), irq.name.clone()),
+        ("total".to_string(), rate(irq.total())),
+    ]);
+    for (cpu, count) in cpus.iter().zip(&irq.counts) {
+        row.insert(cpu.clone(), rate(*count));
+    }
+    row
+}
+
+/// 多次采样的使用率: (平均值, 最小值, 最大值)，没有采样时全为0
+fn usage_stats(samples: &[f32]) -> (f32, f32, f32) {
+    if samples.is_empty() {
+        return (0., 0., 0.);
+    }
+    let avg = samples.iter().sum::<f32>() / samples.len() as f32;
+    let min = samples.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = samples.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    (avg, min, max)
+}
+
+fn is_subtotal(row: &HashMap<String, String>) -> bool {
+    row.get("subtotal").map(|v| v.as_str()) == Some("true")
+}
+
+/// 解析 -h/-H/-B 参数，得到大小的进制和单位
+fn parse_units(human_readable: bool, si: bool, block_size: &str) -> Result<(BaseSize, BlockSize), BlockSizeParseError> {
+    let mut base: BaseSize = BaseSize::Size1024;
+    let mut block: BlockSize = BlockSize::Auto;
+    if human_readable {
+        base = BaseSize::Size1024;
+        block = BlockSize::Auto;
+    } else if si {
+        base = BaseSize::Size1000;
+        block = BlockSize::Auto;
+    }
+
+    if !block_size.is_empty() {
+        block = block_size.parse()?;
+    }
+    Ok((base, block))
+}
+
+/// 解析 --bar 参数，空字符串表示不显示条形图
+fn parse_bar(bar: &str) -> Result<Option<BarStyle>, BarStyleParseError> {
+    if bar.is_empty() {
+        return Ok(None);
+    }
+    bar.parse().map(Some)
+}
+
+fn exclude_record_disk(exclude_key: &str, exclude_vals: &Vec<&str>, row: &HashMap<String, String>) -> bool {
+    //Device列按原始设备名排除，不包含后端文件、dm名称等附加信息
+    let exclude_key = if exclude_key == "device" { "name" } else { exclude_key };
+    let val_opt = row.get(exclude_key);
+    return if let Some(val) = val_opt {
+        for ev in exclude_vals {
+            if ev == val {
+                return true;
             }
         }
         false
-    } else {
-        false
+    } else {
+        false
     };
 }
 
@@ -639,12 +2236,103 @@ fn test_print_system() {
 
 #[test]
 fn test_print_cpu() {
-    SysInfo::new_cpu().print_cpu(false);
+    let args = CpuArgs {
+        details: true,
+        bar: "unicode".to_string(),
+        total: true,
+        interval: "100ms".to_string(),
+        samples: 3,
+        ..CpuArgs::default()
+    };
+    SysInfo::new_cpu().print_cpu(args);
+
+    let args = CpuArgs {
+        breakdown: true,
+        ..CpuArgs::default()
+    };
+    SysInfo::new_cpu().print_cpu(args);
+
+    SysInfo::new_cpu().print_cpu_topology();
+    SysInfo::new_cpu().print_cpu_features();
 }
 
 #[test]
 fn test_print_memory() {
-    SysInfo::new_memory().print_memory();
+    let args = MemoryArgs {
+        bar: "ascii".to_string(),
+        details: true,
+        si: true,
+        block_size: "M".to_string(),
+        swap_top: 5,
+        ..MemoryArgs::default()
+    };
+    SysInfo::new_memory().print_memory(args);
+
+    SysInfo::new_memory().print_free(&FreeOptions { wide: true, total: true, lohi: true, committed: true, ..FreeOptions::default() }, "100ms", 2);
+}
+
+#[test]
+fn test_mount_event() {
+    let event = MountEvent {
+        kind: MountEventKind::Remounted,
+        mount: crate::mount::MountInfo {
+            mount_id: 40,
+            mount_point: "/media/usb".to_string(),
+            source: "/dev/sdb1".to_string(),
+            fs_type: "vfat".to_string(),
+            mount_options: "ro,relatime".to_string(),
+            super_options: "ro".to_string(),
+            ..crate::mount::MountInfo::default()
+        },
+        old_options: Some("rw,relatime".to_string()),
+        old_super_options: Some("rw".to_string()),
+    };
+    let json = mount_event_json("2024-04-11T08:30:00+08:00", &event);
+    assert_eq!(json, r#"{"time":"2024-04-11T08:30:00+08:00","event":"remounted","mount_id":40,"mount_point":"/media/usb","source":"/dev/sdb1","fs_type":"vfat","options":"ro,relatime","old_options":"rw,relatime","super_options":"ro","old_super_options":"rw"}"#);
+    println!("{}", mount_event_text("2024-04-11 08:30:00", &event));
+}
+
+#[test]
+fn test_print_busy() {
+    SysInfo::new().print_busy("/");
+}
+
+#[test]
+fn test_usage_stats() {
+    assert_eq!(usage_stats(&[10., 30., 20.]), (20., 10., 30.));
+    assert_eq!(usage_stats(&[]), (0., 0., 0.));
+}
+
+#[test]
+fn test_print_pressure() {
+    SysInfo::new().with_thresholds("10,40".parse().unwrap()).print_pressure();
+}
+
+#[test]
+fn test_print_irq() {
+    SysInfo::new().print_irq("100ms", true);
+}
+
+#[test]
+fn test_aggregate_disk_usage() {
+    let column = Column::default();
+    let row = |used: &str, total: &str, stale: &str| HashMap::from([
+        ("used".to_string(), used.to_string()),
+        ("total".to_string(), total.to_string()),
+        ("stale".to_string(), stale.to_string()),
+    ]);
+    let (a, b, stale, empty) = (row("25", "100", "false"), row("50", "100", "false"), row("10", "10", "true"), row("0", "0", "false"));
+
+    let usage = |rows: &[&HashMap<String, String>]| aggregate_disk_usage(AggregateArgs { aggregate: Aggregate::Sum, column: &column, rows, base: BaseSize::Size1024, block: BlockSize::Auto });
+    assert_eq!(usage(&[&a, &b, &stale]), "37.50%");
+    assert_eq!(usage(&[&stale]), "-");
+    assert_eq!(usage(&[&empty]), "-");
+    assert_eq!(usage(&[]), "-");
+}
+
+#[test]
+fn test_print_md() {
+    SysInfo::new().print_md();
 }
 
 #[test]
@@ -657,6 +2345,29 @@ fn test_print_disk() {
         human_readable: false,
         si: false,
         block_size: "".to_string(),
+        bar: "ascii".to_string(),
+        group_by: "".to_string(),
+        watch_mounts: false,
+        json: false,
+        busy: "".to_string(),
+        deleted: false,
+    };
+    SysInfo::new().print_disk(cmd);
+
+    let cmd = Commands::Disk {
+        all: false,
+        sort: "Use%".to_string(),
+        exclude: "".to_string(),
+        total: false,
+        human_readable: false,
+        si: true,
+        block_size: "".to_string(),
+        bar: "".to_string(),
+        group_by: "device".to_string(),
+        watch_mounts: false,
+        json: false,
+        busy: "".to_string(),
+        deleted: true,
     };
     SysInfo::new().print_disk(cmd);
 }
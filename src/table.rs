@@ -1,8 +1,10 @@
-use std::fmt;
+use std::{error, fmt};
 use std::collections::HashMap;
+use std::str::FromStr;
 
 use colored::{Color, ColoredString, Colorize, Style, Styles};
 
+use crate::common::{BaseSize, BlockSize};
 use crate::table::CombineString::{AsStr, AsString, AsColoredString};
 
 //标准版本(函数指针render)：已完成
@@ -11,6 +13,11 @@ pub struct Table {
     columns_cache: HashMap<String, usize>,
     data: Vec<HashMap<String, String>>,
     custom: HashMap<String, String>,
+    footers: Vec<Aggregate>,
+    footer_data: Vec<HashMap<String, String>>,
+    /// 合计行中大小列使用的单位
+    base: BaseSize,
+    block: BlockSize,
 
     // columns_cache: HashMap<String, &'a mut Column>,
     // 1. error: lifetime may not live long enough: self.columns_cache.insert(column.key.clone(), column); argument requires that `'1` must outlive `'a`
@@ -37,20 +44,41 @@ impl Table {
         table
     }
 
+    /// 在表格末尾增加合计行，每个 Aggregate 一行
+    pub fn with_footers(mut self, footers: Vec<Aggregate>) -> Self {
+        self.footers = footers;
+        self.refresh_cache();
+        self
+    }
+
+    /// 合计行中大小列的单位，默认1024进制自动选择单位
+    pub fn with_units(mut self, base: BaseSize, block: BlockSize) -> Self {
+        self.base = base;
+        self.block = block;
+        self.refresh_cache();
+        self
+    }
+
     fn refresh_cache(&mut self) {
+        let rows: Vec<&HashMap<String, String>> = self.data.iter().collect();
+        let footer_data = self.footers.iter()
+            .map(|aggregate| aggregate_row(&self.columns, &rows, *aggregate, self.base, self.block))
+            .collect();
+        self.footer_data = footer_data;
+
         for (index, column) in self.columns.iter_mut().enumerate() {
-            let len = column.title.len();
+            let len = column.title.chars().count();
             if column.width < len {
                 column.width = len;
             }
             self.columns_cache.insert(column.key.clone(), index);
         }
 
-        for row in &self.data {
-            for (key, value) in row {
-                if let Some(index) = self.columns_cache.get(key) {
-                    let column = &mut self.columns[*index];
-                    let len = value.len();
+        //多个列可以使用同一个key(例如 Use% 和条形图列)，所以按列遍历
+        for column in self.columns.iter_mut() {
+            for row in self.data.iter().chain(self.footer_data.iter()) {
+                if let Some(value) = row.get(&column.key) {
+                    let len = column.cell_width(value);
                     if column.width < len {
                         column.width = len;
                     }
@@ -60,24 +88,48 @@ impl Table {
     }
 
     fn fmt_header(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let last = self.columns.len() - 1;
-        for (index, column) in self.columns.iter().enumerate() {
-            if index == 0 {
+        let mut first = true;
+        for column in self.columns.iter().filter(|c| !c.hidden) {
+            if first {
                 write!(f, "{}", column)?;
-            } else if index == last {
-                write!(f, "  {}\n", column)?;
+                first = false;
             } else {
                 write!(f, "  {}", column)?;
             }
         }
+        writeln!(f)
+    }
+
+    fn fmt_footer(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, footer) in self.footer_data.iter().enumerate() {
+            if index > 0 || !self.data.is_empty() {
+                writeln!(f)?;
+            }
+            let mut first = true;
+            for column in self.columns.iter().filter(|c| !c.hidden) {
+                let text = footer.get(&column.key).map(|v| v.as_str()).unwrap_or_default();
+                if first {
+                    write!(f, "{}", column.format_footer(text))?;
+                    first = false;
+                } else {
+                    write!(f, "  {}", column.format_footer(text))?;
+                }
+            }
+        }
         Ok(())
     }
 
     fn fmt_row(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let column_last = self.columns.len() - 1;
-        let record_last = self.data.len() - 1;
         for (record_index, record) in self.data.iter().enumerate() {
+            if record_index > 0 {
+                writeln!(f)?;
+            }
+            let mut first = true;
             for (column_index, column) in self.columns.iter().enumerate() {
+                if column.hidden {
+                    continue;
+                }
+
                 let mut text = "";
                 if let Some(value) = record.get(&column.key) {
                     text = value;
@@ -95,10 +147,9 @@ impl Table {
                     custom: &self.custom,
                 };
 
-                if column_index == 0 {
+                if first {
                     write!(f, "{}", column.format(AsStr(text), Some(args)))?;
-                } else if column_index == column_last && record_index != record_last {
-                    write!(f, "  {}\n", column.format(AsStr(text), Some(args)))?;
+                    first = false;
                 } else {
                     write!(f, "  {}", column.format(AsStr(text), Some(args)))?;
                 }
@@ -115,6 +166,10 @@ impl Default for Table {
             columns_cache: HashMap::new(),
             data: Vec::new(),
             custom: HashMap::new(),
+            footers: Vec::new(),
+            footer_data: Vec::new(),
+            base: BaseSize::Size1024,
+            block: BlockSize::Auto,
         }
     }
 }
@@ -123,6 +178,7 @@ impl fmt::Display for Table {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.fmt_header(f)?;
         self.fmt_row(f)?;
+        self.fmt_footer(f)?;
         Ok(())
     }
 }
@@ -135,7 +191,10 @@ pub struct Column {
     pub right_align: bool,
     pub color: Option<Color>,
     pub style: Style,
+    pub thresholds: Option<Thresholds>,
+    pub bar: Option<BarStyle>,
     pub render: Option<Render>,
+    pub aggregate: Option<Aggregator>,
     // pub render: Option<fn(args: RenderArgs) -> CombineString<'a>>, //使用别名类型Render可以不用定义生命周期<'a>
     // fn render(args: RenderArgs) -> CombineString {} //使用函数变量fn不能捕获外部变量，但闭包可以。
     // 函数变量是具体的类型，但闭包不是，每个闭包都是一个单独的类型，即时一模一样的两个闭包也是两个完全不同的类型，所以闭包只能用特征约束。
@@ -158,6 +217,79 @@ pub struct RenderArgs<'a> {
     pub custom: &'a HashMap<String, String>,
 }
 
+/// 合计行的聚合函数: 计算某一列在合计行中显示的文本
+type Aggregator = fn(args: AggregateArgs) -> String;
+
+pub struct AggregateArgs<'a> {
+    pub aggregate: Aggregate,
+    pub column: &'a Column,
+    pub rows: &'a [&'a HashMap<String, String>],
+    pub base: BaseSize,
+    pub block: BlockSize,
+}
+
+/// 合计行的聚合方式
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Aggregate {
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl Aggregate {
+    /// 合计行第一列显示的名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            Aggregate::Sum => "total",
+            Aggregate::Avg => "avg",
+            Aggregate::Min => "min",
+            Aggregate::Max => "max",
+        }
+    }
+
+    /// 聚合数值，Sum 以外的聚合方式在没有数值时返回 None
+    pub fn apply(&self, values: &[f64]) -> Option<f64> {
+        match self {
+            Aggregate::Sum => Some(values.iter().sum()),
+            Aggregate::Avg if values.is_empty() => None,
+            Aggregate::Avg => Some(values.iter().sum::<f64>() / values.len() as f64),
+            Aggregate::Min => values.iter().copied().reduce(f64::min),
+            Aggregate::Max => values.iter().copied().reduce(f64::max),
+        }
+    }
+}
+
+/// 按列计算一行合计值: 有聚合函数的列调用聚合函数，第一列没有聚合函数时显示聚合名称(total/avg/min/max)
+pub fn aggregate_row(columns: &[Column], rows: &[&HashMap<String, String>], aggregate: Aggregate, base: BaseSize, block: BlockSize) -> HashMap<String, String> {
+    let mut row = HashMap::new();
+    for (index, column) in columns.iter().enumerate() {
+        if let Some(aggregator) = column.aggregate {
+            let args = AggregateArgs { aggregate, column, rows, base, block };
+            row.insert(column.key.clone(), aggregator(args));
+        } else if index == 0 {
+            row.insert(column.key.clone(), aggregate.label().to_string());
+        }
+    }
+    row
+}
+
+/// 解析并聚合指定 key 的数值，无法解析的值忽略
+pub fn aggregate_values(aggregate: Aggregate, rows: &[&HashMap<String, String>], key: &str) -> Option<f64> {
+    let values: Vec<f64> = rows.iter().filter_map(|row| row.get(key)?.parse().ok()).collect();
+    aggregate.apply(&values)
+}
+
+/// 百分比列(Use%)的聚合函数
+pub fn aggregate_percent(args: AggregateArgs) -> String {
+    let AggregateArgs { aggregate, column, rows, .. } = args;
+    let values: Vec<f64> = rows.iter().filter_map(|row| parse_percent(row.get(&column.key)?)).collect();
+    match aggregate.apply(&values) {
+        Some(val) => format!("{val:.2}%"),
+        None => String::new(),
+    }
+}
+
 /// 使用泛型<S: AsRef<str>>，最终会导致泛型参数循环依赖，直到编译器报错 Column -> Render -> RenderArgs和CombineString -> Column
 #[derive(Debug)]
 pub enum CombineString<'a> {
@@ -166,7 +298,172 @@ pub enum CombineString<'a> {
     AsColoredString(ColoredString),
 }
 
+/// 百分比列(Use%)的颜色阈值: 小于 warning 为绿色，小于 critical 为黄色，否则为红色
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Thresholds {
+    pub warning: f64,
+    pub critical: f64,
+}
+
+impl Thresholds {
+    pub fn color(&self, percent: f64) -> Color {
+        if percent < self.warning {
+            Color::Green
+        } else if percent < self.critical {
+            Color::Yellow
+        } else {
+            Color::Red
+        }
+    }
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            warning: 70.0,
+            critical: 90.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ThresholdsParseError;
+
+impl fmt::Display for ThresholdsParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid Thresholds")
+    }
+}
+
+impl error::Error for ThresholdsParseError {}
+
+/// 格式: WARN,CRIT 例如 70,90
+impl FromStr for Thresholds {
+    type Err = ThresholdsParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(',').collect();
+        if parts.len() != 2 {
+            return Err(ThresholdsParseError);
+        }
+        let warning: f64 = parts[0].trim().parse().map_err(|_| ThresholdsParseError)?;
+        let critical: f64 = parts[1].trim().parse().map_err(|_| ThresholdsParseError)?;
+        if warning > critical {
+            return Err(ThresholdsParseError);
+        }
+        Ok(Self { warning, critical })
+    }
+}
+
+/// 解析百分比文本，例如 "71.23%" => 71.23
+pub fn parse_percent(text: &str) -> Option<f64> {
+    let num = text.trim().strip_suffix('%')?;
+    let val: f64 = num.trim().parse().ok()?;
+    if val.is_nan() {
+        None
+    } else {
+        Some(val)
+    }
+}
+
+/// 条形图样式
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BarStyle {
+    Ascii,
+    Unicode,
+}
+
+#[derive(Debug)]
+pub struct BarStyleParseError;
+
+impl fmt::Display for BarStyleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid BarStyle")
+    }
+}
+
+impl error::Error for BarStyleParseError {}
+
+impl FromStr for BarStyle {
+    type Err = BarStyleParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ascii" => Ok(BarStyle::Ascii),
+            "unicode" => Ok(BarStyle::Unicode),
+            _ => Err(BarStyleParseError),
+        }
+    }
+}
+
+/// 条形图的格子数
+pub const BAR_WIDTH: usize = 10;
+
+/// 1/8 格的 Unicode 方块字符，下标为占用的八分之几
+const BAR_EIGHTHS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// 渲染百分比条形图，例如 [#######---]  71%
+pub fn render_bar(percent: f64, width: usize, style: BarStyle) -> String {
+    let percent = if percent.is_nan() { 0.0 } else { percent.clamp(0.0, 100.0) };
+    let mut bar = String::with_capacity(width * 3);
+    match style {
+        BarStyle::Ascii => {
+            let filled = (percent / 100.0 * width as f64).round() as usize;
+            for i in 0..width {
+                bar.push(if i < filled { '#' } else { '-' });
+            }
+        }
+        BarStyle::Unicode => {
+            let eighths = (percent / 100.0 * (width * 8) as f64).round() as usize;
+            let full = eighths / 8;
+            for _ in 0..full {
+                bar.push('█');
+            }
+            if full < width {
+                bar.push(BAR_EIGHTHS[eighths % 8]);
+                for _ in full + 1..width {
+                    bar.push(' ');
+                }
+            }
+        }
+    }
+    format!("[{bar}] {percent:>3.0}%")
+}
+
 impl Column {
+    /// 条形图列把百分比文本转换为条形图，非百分比文本保持不变
+    fn bar_text(&self, text: &str) -> Option<String> {
+        let style = self.bar?;
+        let percent = parse_percent(text)?;
+        Some(render_bar(percent, BAR_WIDTH, style))
+    }
+
+    /// 单元格显示宽度(字符数)
+    fn cell_width(&self, text: &str) -> usize {
+        match self.bar_text(text) {
+            Some(bar) => bar.chars().count(),
+            None => text.chars().count(),
+        }
+    }
+
+    /// 合计行单元格: 加粗显示，颜色规则和普通单元格相同
+    fn format_footer(&self, text: &str) -> String {
+        let mut value = text.normal();
+        if !value.is_empty() {
+            value.fgcolor = self.value_color(text);
+            value.style = self.style | Styles::Bold;
+        }
+        self.format(AsColoredString(value), None)
+    }
+
+    /// 单元格的前景色: 设置了阈值且值为百分比时按阈值着色，否则使用列颜色
+    pub fn value_color(&self, text: &str) -> Option<Color> {
+        if let Some(thresholds) = self.thresholds {
+            if let Some(percent) = parse_percent(text) {
+                return Some(thresholds.color(percent));
+            }
+        }
+        self.color
+    }
+
     fn format(&self, value: CombineString, args: Option<RenderArgs>) -> String {
         // fn format<S: AsRef<str>>(&self, value: S) -> String {}
         // let value: &str = value.as_ref();
@@ -182,11 +479,11 @@ impl Column {
                 },
             };
 
-            let output = match value {
+            let mut output = match value {
                 AsStr(val) => {
                     let mut val = val.normal();
                     //处理颜色
-                    if let Some(c) = self.color {
+                    if let Some(c) = self.value_color(&val.input) {
                         val.fgcolor = Some(c);
                     }
                     //处理样式
@@ -196,7 +493,7 @@ impl Column {
                 AsString(val) => {
                     let mut val = val.normal();
                     //处理颜色
-                    if let Some(c) = self.color {
+                    if let Some(c) = self.value_color(&val.input) {
                         val.fgcolor = Some(c);
                     }
                     //处理样式
@@ -206,6 +503,11 @@ impl Column {
                 AsColoredString(val) => val,
             };
 
+            //处理条形图，颜色已按原始百分比计算
+            if let Some(bar) = self.bar_text(&output.input) {
+                output.input = bar;
+            }
+
             //处理对齐
             let width = self.width;
             if self.right_align {
@@ -227,7 +529,10 @@ impl Default for Column {
             right_align: false,
             color: None,
             style: Style::default(),
+            thresholds: None,
+            bar: None,
             render: None,
+            aggregate: None,
         }
     }
 }
@@ -353,6 +658,83 @@ fn test_table() {
     println!("{}", table);
 }
 
+#[test]
+fn test_thresholds() {
+    let thresholds: Thresholds = "70,90".parse().unwrap();
+    assert_eq!(thresholds, Thresholds::default());
+    assert!("90,70".parse::<Thresholds>().is_err());
+    assert!("abc".parse::<Thresholds>().is_err());
+
+    let column = Column {
+        color: Some(Color::Cyan),
+        thresholds: Some(thresholds),
+        ..Column::default()
+    };
+    assert_eq!(column.value_color("3.00%"), Some(Color::Green));
+    assert_eq!(column.value_color("70.00%"), Some(Color::Yellow));
+    assert_eq!(column.value_color("99.99%"), Some(Color::Red));
+    assert_eq!(column.value_color("NaN%"), Some(Color::Cyan));
+    assert_eq!(column.value_color(""), Some(Color::Cyan));
+}
+
+#[test]
+fn test_render_bar() {
+    assert_eq!(render_bar(71.0, 10, BarStyle::Ascii), "[#######---]  71%");
+    assert_eq!(render_bar(0.0, 10, BarStyle::Ascii), "[----------]   0%");
+    assert_eq!(render_bar(100.0, 10, BarStyle::Ascii), "[##########] 100%");
+    assert_eq!(render_bar(150.0, 4, BarStyle::Ascii), "[####] 100%");
+    assert_eq!(render_bar(75.0, 4, BarStyle::Unicode), "[███ ]  75%");
+    assert_eq!(render_bar(62.5, 2, BarStyle::Unicode), "[█▎]  62%");
+    assert_eq!(render_bar(100.0, 2, BarStyle::Unicode), "[██] 100%");
+    assert_eq!("Unicode".parse::<BarStyle>().unwrap(), BarStyle::Unicode);
+    assert!("block".parse::<BarStyle>().is_err());
+
+    let column = Column {
+        bar: Some(BarStyle::Ascii),
+        ..Column::default()
+    };
+    assert_eq!(column.cell_width("71.23%"), 17);
+    assert_eq!(column.cell_width("-"), 1);
+}
+
+#[test]
+fn test_footer() {
+    let columns = vec![
+        Column {
+            title: "Name".to_string(),
+            key: "name".to_string(),
+            ..Column::default()
+        },
+        Column {
+            title: "Use%".to_string(),
+            key: "usage".to_string(),
+            right_align: true,
+            aggregate: Some(aggregate_percent),
+            ..Column::default()
+        },
+    ];
+    let data = vec![
+        HashMap::from([("name".to_string(), "cpu0".to_string()), ("usage".to_string(), "10.00%".to_string())]),
+        HashMap::from([("name".to_string(), "cpu1".to_string()), ("usage".to_string(), "30.00%".to_string())]),
+        HashMap::from([("name".to_string(), "cpu2".to_string()), ("usage".to_string(), "-".to_string())]),
+    ];
+    let table = Table::new(columns, data, HashMap::new())
+        .with_footers(vec![Aggregate::Avg, Aggregate::Min, Aggregate::Max, Aggregate::Sum]);
+
+    assert_eq!(table.footer_data[0].get("name").unwrap(), "avg");
+    assert_eq!(table.footer_data[0].get("usage").unwrap(), "20.00%");
+    assert_eq!(table.footer_data[1].get("usage").unwrap(), "10.00%");
+    assert_eq!(table.footer_data[2].get("usage").unwrap(), "30.00%");
+    assert_eq!(table.footer_data[3].get("name").unwrap(), "total");
+    assert_eq!(table.footer_data[3].get("usage").unwrap(), "40.00%");
+    println!("{}", table);
+
+    assert_eq!(Aggregate::Avg.apply(&[]), None);
+    assert_eq!(Aggregate::Sum.apply(&[]), Some(0.0));
+    let rows = vec![&table.data[0], &table.data[1]];
+    assert_eq!(aggregate_values(Aggregate::Max, &rows, "usage"), None);
+}
+
 #[test]
 fn test4() {
     use std::cell::RefCell;